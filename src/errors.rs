@@ -21,6 +21,15 @@ pub enum SonioxWindowsErrors {
     LoggingRuntime(#[from] ConfigErrors),
     #[error("Error in string UTF-8: {0}")]
     Utf8(#[from] std::str::Utf8Error),
+    #[error("Soniox rejected the API key: {0}")]
+    SonioxAuth(String),
+    #[error("Soniox error {code}: {message}")]
+    SonioxApi { code: u16, message: String },
+    #[error("Soniox closed the connection (code {code}): {reason}")]
+    SonioxServerClosed { code: u16, reason: String },
+    /// A capture setting the device can't satisfy; retrying won't help.
+    #[error("Audio configuration error: {0}")]
+    AudioConfig(String),
     #[error("Internal error: {0}")]
     Internal(String),
 }
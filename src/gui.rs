@@ -1,4 +1,5 @@
 pub mod app;
 pub mod draw;
+pub mod fonts;
 pub mod text;
 pub mod utils;
@@ -1,105 +1,555 @@
-use crate::gui::draw::draw_text_with_shadow;
-use crate::soniox::state::TranscriptionState;
+use crate::errors::SonioxWindowsErrors;
+use crate::gui::draw::{TextStyle, draw_notice, draw_paused, draw_status_dot, draw_text_with_shadow};
+use crate::soniox::state::{FinalSegment, TranscriptionState};
+use crate::soniox::validation::test_api_key;
 use crate::types::audio::AudioMessage;
-use crate::types::soniox::SonioxTranscriptionResponse;
-use crate::windows::utils::{initialize_tool_window, initialize_window, make_window_click_through};
-use eframe::egui::{CentralPanel, Context, Visuals};
+use crate::types::languages::LanguageHint;
+use crate::types::settings::{
+    ControlStrip, SettingsApp, StatusIndicator, ZOrder, save_font_size, save_window_offset, save_window_size,
+};
+use crate::types::soniox::{ControlMsg, SonioxTranscriptionResponse, StreamStatus};
+use crate::windows::utils::{
+    HotkeyAction, cursor_client_pos, get_monitor_rect, initialize_tool_window, initialize_window, is_move_modifier_held,
+    make_window_click_through,
+};
+use eframe::egui::viewport::ResizeDirection;
+use eframe::egui::{
+    Align2, Area, CentralPanel, Context, FontId, Id, LayerId, Order, Pos2, Rect, Sense, Vec2, ViewportCommand, Visuals,
+};
 use eframe::epaint::Color32;
 use eframe::{App, Frame};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
 
 const POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// Fill colors handed out to speakers in the order they first appear.
+const SPEAKER_PALETTE: [Color32; 6] = [
+    Color32::from_rgb(255, 235, 120),
+    Color32::from_rgb(120, 210, 255),
+    Color32::from_rgb(150, 240, 150),
+    Color32::from_rgb(255, 160, 200),
+    Color32::from_rgb(255, 180, 110),
+    Color32::from_rgb(200, 170, 255),
+];
+/// Text colors of the extra `target_languages` lanes, in the order they are listed.
+const TARGET_PALETTE: [Color32; 3] = [
+    Color32::from_rgb(120, 210, 255),
+    Color32::from_rgb(150, 240, 150),
+    Color32::from_rgb(255, 180, 110),
+];
+/// Measured when there's no recent block long enough to be representative.
+const WIDTH_SAMPLE: &str = "The quick brown fox jumps over the lazy dog";
+const MIN_SAMPLE_CHARS: usize = 20;
+/// Font size change per hotkey press or Ctrl+scroll notch.
+const FONT_SIZE_STEP: f32 = 2.0;
+const FONT_SIZE_RANGE: std::ops::RangeInclusive<f32> = 8.0..=200.0;
+/// On exit, how long to wait for Soniox to return the last tokens.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(4);
+/// How often the config file's modification time is checked for hot reload.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Side of the resize grip drawn in the bottom-right corner while the overlay is movable.
+const RESIZE_GRIP: f32 = 16.0;
+/// Height of the clickable strip with `allow_interaction`, in points.
+const CONTROL_STRIP_HEIGHT: f32 = 32.0;
+/// How long a stream notice (e.g. a Soniox error) stays on screen.
+const NOTICE_DURATION: Duration = Duration::from_secs(5);
 
 use crate::soniox::modes::SonioxMode;
 
+/// An extra one-way target language: its stream's responses and its own caption buffer,
+/// drawn in a band below the main captions.
+struct TargetLane {
+    rx_transcription: UnboundedReceiver<SonioxTranscriptionResponse>,
+    state: TranscriptionState,
+}
+
 pub struct SubtitlesApp {
     rx_transcription: UnboundedReceiver<SonioxTranscriptionResponse>,
+    rx_notice: UnboundedReceiver<String>,
+    notice: Option<(String, Instant)>,
+    rx_status: UnboundedReceiver<StreamStatus>,
+    /// Last status reported by the stream; nothing is drawn before the first report.
+    stream_status: Option<StreamStatus>,
+    status_indicator: StatusIndicator,
+    status_corner: Align2,
     tx_audio: UnboundedSender<AudioMessage>,
     tx_exit: UnboundedSender<bool>,
+    tx_control: UnboundedSender<ControlMsg>,
+    /// Disconnects once every stream task has finished; on_exit waits for it.
+    rx_stream_done: std::sync::mpsc::Receiver<()>,
+    rx_hotkey: UnboundedReceiver<HotkeyAction>,
+    /// Errors a background task can't recover from (e.g. no audio device); the overlay closes on one.
+    rx_fatal: UnboundedReceiver<String>,
+    /// Captions are hidden (and, with `pause_stops_audio`, no audio is sent) until toggled back.
+    paused: bool,
+    pause_stops_audio: bool,
+    /// Model name typed into the debug window.
+    model_input: String,
+    /// Real-time models from the models cache, offered in the debug window's dropdown.
+    known_models: Vec<String>,
+    /// Answer to the debug window's "Check API key", while it is running.
+    key_check: Option<oneshot::Receiver<Result<Vec<String>, SonioxWindowsErrors>>>,
+    /// Outcome of the last key check, shown next to its button.
+    key_status: Option<String>,
     initialized_windows: bool,
     enable_high_priority: bool,
-    font_size: f32,
-    text_color: Color32,
+    z_order: ZOrder,
+    style: TextStyle,
+    font_size_overrides: HashMap<LanguageHint, f32>,
+    /// Stable per-speaker fill colors, assigned from SPEAKER_PALETTE on first sight.
+    speaker_colors: HashMap<String, Color32>,
     subtitles_state: TranscriptionState,
+    target_lanes: Vec<TargetLane>,
     show_window_border: bool,
     interim_current_height: f32,
-    debug_window_enabled: bool,
+    /// Whether the debug window is shown; its close button and the debug hotkey flip it at runtime.
+    debug_window_open: bool,
+    /// Size and position the debug window opens with; moving or resizing it afterwards sticks.
+    debug_window_size: (f32, f32),
+    debug_window_pos: Option<(f32, f32)>,
+    /// Where the window sits when `window_offset` is zero; used to turn a dragged position back into an offset.
+    anchor_origin: (f32, f32),
+    config_path: String,
+    enable_drag_move: bool,
+    /// With `lock_window_size = false` the overlay can also be resized while movable.
+    resizable: bool,
+    /// Keeps `control_strip` clickable; its buttons show while the mouse is over it.
+    allow_interaction: bool,
+    control_strip: ControlStrip,
+    /// Window position when Ctrl went down, while the overlay is movable.
+    move_start: Option<Pos2>,
+    /// Inner size when Ctrl went down, to tell whether it was resized.
+    size_start: Option<Vec2>,
+    /// Font size was changed with Ctrl+scroll and still has to be saved.
+    font_size_dirty: bool,
+    /// (font size, sample text, average char advance) from the last measurement.
+    char_width_cache: Option<(f32, String, f32)>,
+    /// Settings currently applied, to tell which fields a reload changed.
+    active_settings: Option<SettingsApp>,
+    config_modified: Option<SystemTime>,
+    config_checked: Instant,
+    /// Mirror of the visible lines for an OBS text source, and what was last written there.
+    obs_text_file: Option<String>,
+    obs_text: String,
     mode: Box<dyn SonioxMode + Send + Sync>, 
 }
 
 impl SubtitlesApp {
     pub fn new(
         rx_transcription: UnboundedReceiver<SonioxTranscriptionResponse>,
+        rx_notice: UnboundedReceiver<String>,
+        rx_status: UnboundedReceiver<StreamStatus>,
         tx_exit: UnboundedSender<bool>,
         tx_audio: UnboundedSender<AudioMessage>,
-        enable_high_priority: bool,
-        font_size: f32,
-        text_color: Color32,
-        show_window_border: bool,
-        window_width: f32,
-        debug_window_enabled: bool,
-        show_interim: bool,
-        stability_timeout_ms: u64,
-        save_transcription: bool,
-        transcript_save_path: &str,
+        tx_control: UnboundedSender<ControlMsg>,
+        rx_stream_done: std::sync::mpsc::Receiver<()>,
+        rx_hotkey: UnboundedReceiver<HotkeyAction>,
+        rx_fatal: UnboundedReceiver<String>,
+        settings: &SettingsApp,
+        known_models: Vec<String>,
+        anchor_origin: (f32, f32),
+        word_filter: &[String],
         mode: Box<dyn SonioxMode + Send + Sync>,
     ) -> Self {
-        // ... (preserving logic)
-        let usable_width = window_width * 0.88;
+        // Rough estimate until fonts are available and the first frame measures real glyphs.
+        let window_width = settings.window_width();
+        let font_size = settings.font_size();
+        let side_by_side = settings.side_by_side();
+        let usable_width = (if side_by_side { window_width / 2.0 } else { window_width }) * settings.wrap_width_ratio();
         let avg_char_width = font_size * 0.46;
         let chars_per_line = usable_width / avg_char_width;
         let max_chars = ((chars_per_line * 0.95) as usize).max(50);
+        // Both sizes are in points, so this doesn't change with display scaling.
+        log::info!("Initial max_chars: {} (window_width {}, font_size {})", max_chars, window_width, font_size);
 
-        let mut subtitles_state = TranscriptionState::new(50, max_chars);
-        subtitles_state.set_stability_params(show_interim, stability_timeout_ms);
-        subtitles_state.set_logging(save_transcription, transcript_save_path);
+        let mut subtitles_state = TranscriptionState::new(settings.max_lines(), max_chars);
+        apply_state_settings(&mut subtitles_state, settings, word_filter);
+        subtitles_state.set_logging(settings.save_transcription(), settings.transcript_save_path(), settings.transcript_format());
+        subtitles_state.set_export(settings.export_format(), settings.transcript_save_path());
 
         Self {
             rx_transcription,
+            rx_notice,
+            notice: None,
+            rx_status,
+            stream_status: None,
+            status_indicator: settings.show_status_indicator(),
+            status_corner: settings.status_indicator_corner(),
             tx_exit,
             tx_audio,
-            enable_high_priority,
-            font_size,
-            text_color,
+            tx_control,
+            rx_stream_done,
+            rx_hotkey,
+            rx_fatal,
+            paused: false,
+            pause_stops_audio: settings.pause_stops_audio(),
+            model_input: settings.model().to_string(),
+            known_models,
+            key_check: None,
+            key_status: None,
+            enable_high_priority: settings.enable_high_priority(),
+            z_order: settings.z_order(),
+            style: TextStyle {
+                font_size,
+                text_color: settings.text_color(),
+                show_language_tags: settings.detect_language(),
+                show_speaker_labels: settings.show_speaker_labels(),
+                background: settings.text_background(),
+                align: settings.text_align(),
+                direction: settings.text_direction(),
+                fade_old_lines: settings.fade_old_lines(),
+                flow: settings.flow_direction(),
+                wrap_width_ratio: settings.wrap_width_ratio(),
+                side_by_side,
+            },
+            font_size_overrides: settings.font_size_overrides(),
+            speaker_colors: HashMap::new(),
             initialized_windows: false,
             subtitles_state,
-            show_window_border,
+            target_lanes: Vec::new(),
+            show_window_border: settings.show_window_border(),
             interim_current_height: 0.0,
-            debug_window_enabled,
+            debug_window_open: settings.debug_window(),
+            debug_window_size: settings.debug_window_size(),
+            debug_window_pos: settings.debug_window_pos(),
+            anchor_origin,
+            config_path: settings.config_path().to_string(),
+            enable_drag_move: settings.enable_drag_move(),
+            resizable: !settings.lock_window_size(),
+            allow_interaction: settings.allow_interaction(),
+            control_strip: settings.control_strip(),
+            move_start: None,
+            size_start: None,
+            font_size_dirty: false,
+            char_width_cache: None,
+            active_settings: SettingsApp::new(settings.config_path()).ok(),
+            config_modified: config_modified(settings.config_path()),
+            config_checked: Instant::now(),
+            obs_text_file: settings.obs_text_file().map(str::to_string),
+            obs_text: String::new(),
             mode,
         }
     }
 }
 
+impl SubtitlesApp {
+    /// Checks the configured API key in the background; poll_key_check picks up the answer.
+    fn start_key_check(&mut self) {
+        let Some(settings) = &self.active_settings else {
+            return;
+        };
+        let api_key = settings.api_key().to_string();
+        let (tx, rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = tx.send(test_api_key(api_key).await);
+        });
+        self.key_check = Some(rx);
+        self.key_status = Some("Checking...".to_string());
+    }
+
+    /// A successful check also refreshes the model dropdown with what the key can use.
+    fn poll_key_check(&mut self) {
+        let Some(rx) = &mut self.key_check else {
+            return;
+        };
+        let status = match rx.try_recv() {
+            Ok(Ok(models)) => {
+                let status = format!("Key OK, {} real-time models", models.len());
+                self.known_models = models;
+                status
+            }
+            Ok(Err(e)) => format!("Key check failed: {}", e),
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => "Key check failed".to_string(),
+        };
+        self.key_status = Some(status);
+        self.key_check = None;
+    }
+
+    /// Registers a receiver for every committed final segment (e.g. the caption server).
+    pub(crate) fn add_final_sink(&mut self, sink: UnboundedSender<FinalSegment>) {
+        self.subtitles_state.add_final_sink(sink);
+    }
+
+    /// Shows the captions of another one-way target language in a band of its own.
+    pub(crate) fn add_target_lane(
+        &mut self,
+        language: LanguageHint,
+        rx_transcription: UnboundedReceiver<SonioxTranscriptionResponse>,
+        settings: &SettingsApp,
+        word_filter: &[String],
+    ) {
+        let mut state = TranscriptionState::new(settings.max_lines(), self.subtitles_state.get_max_chars());
+        apply_state_settings(&mut state, settings, word_filter);
+        log::info!("Showing {} translation in lane {}", language.code(), self.target_lanes.len() + 1);
+        self.target_lanes.push(TargetLane { rx_transcription, state });
+    }
+
+    /// Mirrors the visible lines into `obs_text_file` whenever they change.
+    fn write_obs_text_file(&mut self) {
+        let Some(path) = &self.obs_text_file else {
+            return;
+        };
+        let text = self.subtitles_state.visible_text();
+        if text == self.obs_text {
+            return;
+        }
+        if let Err(e) = write_atomically(path, &text) {
+            log::warn!("Failed to write obs_text_file {}: {}", path, e);
+        }
+        self.obs_text = text;
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        log::info!("Captions {}", if self.paused { "paused" } else { "resumed" });
+        if self.pause_stops_audio {
+            let _ = self.tx_control.send(ControlMsg::SetPaused(self.paused));
+        }
+    }
+
+    fn clear_captions(&mut self, ctx: &Context) {
+        // Apply whatever is already queued first, or it would repaint the old text.
+        self.subtitles_state.flush_pending_events(self.mode.as_ref());
+        self.subtitles_state.clear();
+        for lane in &mut self.target_lanes {
+            lane.state.flush_pending_events(self.mode.as_ref());
+            lane.state.clear();
+        }
+        ctx.request_repaint();
+    }
+
+    fn commit_interim(&mut self, ctx: &Context) {
+        // Apply what is queued first, so the newest interim text is the one committed.
+        self.subtitles_state.flush_pending_events(self.mode.as_ref());
+        self.subtitles_state.commit_interim();
+        for lane in &mut self.target_lanes {
+            lane.state.flush_pending_events(self.mode.as_ref());
+            lane.state.commit_interim();
+        }
+        ctx.request_repaint();
+    }
+
+    /// The `allow_interaction` strip, in the overlay's own points.
+    fn control_strip_rect(&self, ctx: &Context) -> Rect {
+        let window = ctx.content_rect();
+        let height = CONTROL_STRIP_HEIGHT.min(window.height());
+        match self.control_strip {
+            ControlStrip::Top => Rect::from_min_size(window.min, Vec2::new(window.width(), height)),
+            ControlStrip::Bottom => Rect::from_min_max(Pos2::new(window.left(), window.bottom() - height), window.max),
+        }
+    }
+
+    /// Whether the mouse is over the control strip. The window may be click-through, so egui
+    /// doesn't know where the mouse is; the position comes from Windows instead.
+    fn is_over_control_strip(&self, ctx: &Context, frame: &Frame) -> bool {
+        if !self.allow_interaction {
+            return false;
+        }
+        let scale = ctx.pixels_per_point();
+        cursor_client_pos(frame)
+            .is_some_and(|(x, y)| self.control_strip_rect(ctx).contains(Pos2::new(x / scale, y / scale)))
+    }
+
+    /// Pause/Resume and Clear buttons, drawn above the captions.
+    fn draw_control_strip(&mut self, ctx: &Context) {
+        let strip = self.control_strip_rect(ctx);
+        let mut toggle = false;
+        let mut clear = false;
+        Area::new(Id::new("control_strip"))
+            .order(Order::Foreground)
+            .fixed_pos(strip.min)
+            .show(ctx, |ui| {
+                ui.painter().rect_filled(strip, 0.0, Color32::from_black_alpha(160));
+                ui.set_min_size(strip.size());
+                ui.horizontal_centered(|ui| {
+                    toggle = ui.button(if self.paused { "Resume" } else { "Pause" }).clicked();
+                    clear = ui.button("Clear").clicked();
+                });
+            });
+        if toggle {
+            self.toggle_pause();
+        }
+        if clear {
+            self.clear_captions(ctx);
+        }
+    }
+
+    /// Changes the font size at runtime; max_chars follows on the next frame.
+    fn adjust_font_size(&mut self, delta: f32, persist: bool) {
+        let size = (self.style.font_size + delta).clamp(*FONT_SIZE_RANGE.start(), *FONT_SIZE_RANGE.end());
+        if size == self.style.font_size {
+            return;
+        }
+        self.style.font_size = size;
+        self.subtitles_state.log_debug(format!("FONT: {:.1}", size));
+        if persist {
+            self.persist_font_size();
+        }
+    }
+
+    /// Average advance of one char at the current font size. Measured on the newest block when it is
+    /// long enough, so CJK (wide) and Latin (narrow) text each get a realistic line length.
+    /// Only re-measured when the font size or the sample changes.
+    fn measure_char_width(&mut self, ctx: &Context) -> f32 {
+        let sample = self
+            .subtitles_state
+            .finishes_lines
+            .front()
+            .map(|line| line.text.trim())
+            .filter(|text| text.chars().count() >= MIN_SAMPLE_CHARS)
+            .unwrap_or(WIDTH_SAMPLE)
+            .to_string();
+        if let Some((size, cached, width)) = &self.char_width_cache {
+            if *size == self.style.font_size && *cached == sample {
+                return *width;
+            }
+        }
+        let galley = ctx.layer_painter(LayerId::background()).layout_no_wrap(
+            sample.clone(),
+            FontId::proportional(self.style.font_size),
+            Color32::WHITE,
+        );
+        let width = (galley.size().x / sample.chars().count() as f32).max(1.0);
+        self.char_width_cache = Some((self.style.font_size, sample, width));
+        width
+    }
+
+    /// Re-reads the config file when it changed on disk and applies what can change live.
+    /// Anything that only takes effect at startup is reported as requiring a restart.
+    fn reload_config_if_changed(&mut self, ctx: &Context) {
+        if self.config_checked.elapsed() < CONFIG_POLL_INTERVAL {
+            return;
+        }
+        self.config_checked = Instant::now();
+        let modified = config_modified(&self.config_path);
+        if modified == self.config_modified {
+            return;
+        }
+        self.config_modified = modified;
+
+        let settings = match SettingsApp::new(&self.config_path) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::warn!("Config reload: failed to read {}: {}", self.config_path, e);
+                return;
+            }
+        };
+        if let Err(e) = settings.validate() {
+            log::warn!("Config reload: {} ignored: {}", self.config_path, e);
+            self.notice = Some((format!("Config not reloaded: {}", e), Instant::now()));
+            return;
+        }
+        let word_filter = match settings.word_filter() {
+            Ok(word_filter) => word_filter,
+            Err(e) => {
+                log::warn!("Config reload: {}", e);
+                return;
+            }
+        };
+        log::info!("Config reload: applying {}", self.config_path);
+
+        self.style.font_size = settings.font_size();
+        self.font_size_overrides = settings.font_size_overrides();
+        self.style.text_color = settings.text_color();
+        self.style.background = settings.text_background();
+        self.style.align = settings.text_align();
+        self.style.direction = settings.text_direction();
+        self.style.fade_old_lines = settings.fade_old_lines();
+        self.style.show_speaker_labels = settings.show_speaker_labels();
+        self.style.flow = settings.flow_direction();
+        self.style.wrap_width_ratio = settings.wrap_width_ratio();
+        self.show_window_border = settings.show_window_border();
+        self.enable_drag_move = settings.enable_drag_move();
+        self.allow_interaction = settings.allow_interaction();
+        self.control_strip = settings.control_strip();
+        self.status_indicator = settings.show_status_indicator();
+        self.debug_window_size = settings.debug_window_size();
+        self.debug_window_pos = settings.debug_window_pos();
+        self.status_corner = settings.status_indicator_corner();
+        self.pause_stops_audio = settings.pause_stops_audio();
+        apply_state_settings(&mut self.subtitles_state, &settings, &word_filter);
+        for lane in &mut self.target_lanes {
+            apply_state_settings(&mut lane.state, &settings, &word_filter);
+        }
+
+        if let Some(previous) = &self.active_settings {
+            let (origin_x, origin_y) = self.anchor_origin;
+            let (offset_x, offset_y) = settings.window_offset();
+            if previous.window_offset() != settings.window_offset() {
+                ctx.send_viewport_cmd(ViewportCommand::OuterPosition(Pos2::new(origin_x + offset_x, origin_y + offset_y)));
+            }
+            let size = (settings.window_width(), settings.window_height());
+            if self.resizable && (previous.window_width(), previous.window_height()) != size {
+                ctx.send_viewport_cmd(ViewportCommand::InnerSize(Vec2::new(size.0, size.1)));
+            }
+            // Only an edit to `debug_window` itself overrides how the user left the window.
+            if previous.debug_window() != settings.debug_window() {
+                self.debug_window_open = settings.debug_window();
+            }
+            let restart = previous.restart_required_changes(&settings);
+            if !restart.is_empty() {
+                log::info!("Config reload: {} changed; requires restart", restart.join(", "));
+                self.notice = Some((format!("Restart to apply: {}", restart.join(", ")), Instant::now()));
+            }
+        }
+        self.active_settings = Some(settings);
+        ctx.request_repaint();
+    }
+
+    fn persist_font_size(&self) {
+        match save_font_size(&self.config_path, self.style.font_size) {
+            Ok(()) => log::info!("Saved font_size = {:.1}", self.style.font_size),
+            Err(e) => log::warn!("Failed to save font_size to {}: {}", self.config_path, e),
+        }
+    }
+}
+
 impl App for SubtitlesApp {
     fn update(&mut self, ctx: &Context, frame: &mut Frame) {
         let mut app_frame = eframe::egui::Frame::default().fill(Color32::TRANSPARENT);
         if self.show_window_border {
-            app_frame = app_frame.stroke(eframe::egui::Stroke::new(2.0, self.text_color));
+            app_frame = app_frame.stroke(eframe::egui::Stroke::new(2.0, self.style.text_color));
         }
 
         // Capture main window rect for debug info
         let main_rect = ctx.input(|i| i.viewport().inner_rect.unwrap_or(eframe::egui::Rect::ZERO));
 
-        // Dynamically update max_chars based on current window width
-        // Middle Ground Tuning: 88% width, 0.46 char width factor.
-        // This allows more text than the conservative default (0.8/0.5) 
-        // Recalculate max chars based on current window width
-        let usable_width = main_rect.width() * 0.88;
-        let avg_char_width = self.font_size * 0.46;
+        // Dynamically update max_chars based on current window width and measured char width,
+        // over the same share of the width that draw_text_with_shadow wraps at.
+        let usable_width = self.style.wrap_width(main_rect.width());
+        let avg_char_width = self.measure_char_width(ctx);
         let chars_per_line = usable_width / avg_char_width;
-        let max_chars = (chars_per_line as usize).max(50);
+        let max_chars = (chars_per_line as usize).max(10);
         self.subtitles_state.set_max_chars(max_chars);
+        for lane in &mut self.target_lanes {
+            lane.state.set_max_chars(max_chars);
+        }
+
+        self.reload_config_if_changed(ctx);
+        if let Ok(reason) = self.rx_fatal.try_recv() {
+            // Closing goes through on_exit, so the transcript and export are still finished.
+            log::error!("Closing the overlay: {}", reason);
+            ctx.send_viewport_cmd(ViewportCommand::Close);
+        }
+        let display_scale = ctx.pixels_per_point();
 
         // Separate Native Debug Window
-        if self.debug_window_enabled {
+        if self.debug_window_open {
+            // egui only re-applies builder fields that change, so this doesn't undo the user's moves.
+            let mut builder = eframe::egui::ViewportBuilder::default()
+                .with_title("SoniLiveText Debug")
+                .with_inner_size([self.debug_window_size.0, self.debug_window_size.1])
+                .with_always_on_top();
+            if let Some((x, y)) = self.debug_window_pos {
+                builder = builder.with_position([x, y]);
+            }
             ctx.show_viewport_immediate(
                 eframe::egui::ViewportId::from_hash_of("debug_viewport"),
-                eframe::egui::ViewportBuilder::default()
-                    .with_title("SoniLiveText Debug")
-                    .with_inner_size([300.0, 500.0])
-                    .with_always_on_top(),
+                builder,
                 |ctx, _class| {
                     eframe::egui::CentralPanel::default().show(ctx, |ui| {
                         ui.heading("Debug Info");
@@ -111,11 +561,45 @@ impl App for SubtitlesApp {
                         ui.label(format!("Main Window: {:.0} x {:.0}", main_rect.width(), main_rect.height()));
                         
                         ui.label(format!("Interim Height: {:.2}", self.interim_current_height));
-                        ui.label(format!("Font Size: {:.1}", self.font_size));
+                        ui.label(format!("Font Size: {:.1}", self.style.font_size));
+                        ui.label(format!("Display Scale: {:.0}%", display_scale * 100.0));
                         if self.subtitles_state.get_active_char_count() > self.subtitles_state.get_max_chars() {
                             ui.colored_label(Color32::RED, "OVERFLOW / FREEZING");
                         }
                         
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.label("Model:");
+                            if !self.known_models.is_empty() {
+                                eframe::egui::ComboBox::from_id_salt("model_select")
+                                    .selected_text("Known")
+                                    .show_ui(ui, |ui| {
+                                        for model in &self.known_models {
+                                            ui.selectable_value(&mut self.model_input, model.clone(), model);
+                                        }
+                                    });
+                            }
+                            // Free text stays available for models newer than the cached list.
+                            ui.text_edit_singleline(&mut self.model_input);
+                            let model = self.model_input.trim();
+                            if ui.add_enabled(!model.is_empty(), eframe::egui::Button::new("Switch")).clicked() {
+                                let _ = self.tx_control.send(ControlMsg::SetModel(model.to_string()));
+                            }
+                        });
+                        self.poll_key_check();
+                        ui.horizontal(|ui| {
+                            let can_check = self.key_check.is_none() && self.active_settings.is_some();
+                            if ui.add_enabled(can_check, eframe::egui::Button::new("Check API key")).clicked() {
+                                self.start_key_check();
+                            }
+                            if let Some(status) = &self.key_status {
+                                ui.label(status);
+                            }
+                        });
+                        if self.key_check.is_some() {
+                            ctx.request_repaint_after(Duration::from_millis(200));
+                        }
+
                         ui.separator();
                         ui.label("Recent Events:");
                         eframe::egui::ScrollArea::vertical().max_height(ui.available_height() - 20.0).show(ui, |ui| {
@@ -125,27 +609,87 @@ impl App for SubtitlesApp {
                         });
                     });
 
+                    // The viewport closes once it is no longer shown; the debug hotkey reopens it.
                     if ctx.input(|i| i.viewport().close_requested()) {
-                        // How to handle close? Just ignore or hide?
-                        // For now, let it close, but next frame it might reappear if we call this again?
-                        // Actually show_viewport_immediate re-creates it if needed.
-                        // If user closes it, maybe we should stop calling it?
-                        // But for dev, let's keep it persistent.
+                        self.debug_window_open = false;
                     }
                 },
             );
         }
 
+        let over_controls = self.is_over_control_strip(ctx, frame);
+        if over_controls {
+            self.draw_control_strip(ctx);
+        }
+
         CentralPanel::default()
             .frame(app_frame)
             .show(ctx, |ui| {
-                make_window_click_through(frame);
+                let outer_pos = ctx.input(|i| i.viewport().outer_rect).map(|r| r.min);
+                let inner_size = ctx.input(|i| i.viewport().inner_rect).map(|r| r.size());
+                let movable = self.enable_drag_move && is_move_modifier_held();
+                make_window_click_through(frame, !movable && !over_controls);
+                if movable {
+                    if self.move_start.is_none() {
+                        self.move_start = outer_pos;
+                        self.size_start = inner_size;
+                    }
+                    let response = ui.interact(ui.max_rect(), Id::new("drag_overlay"), Sense::drag());
+                    if response.drag_started() {
+                        ctx.send_viewport_cmd(ViewportCommand::StartDrag);
+                    }
+                    if self.resizable {
+                        // Registered after the drag area, so it wins the hit test in its corner.
+                        let corner = ui.max_rect().right_bottom();
+                        let grip = Rect::from_min_max(corner - Vec2::splat(RESIZE_GRIP), corner);
+                        let grip_response = ui.interact(grip, Id::new("resize_overlay"), Sense::drag());
+                        if grip_response.drag_started() {
+                            ctx.send_viewport_cmd(ViewportCommand::BeginResize(ResizeDirection::SouthEast));
+                        }
+                        ui.painter().rect_filled(grip, 0.0, self.style.text_color);
+                    }
+                    let scroll = ctx.input(|i| i.raw_scroll_delta.y);
+                    if scroll != 0.0 {
+                        // Saved once Ctrl is released rather than on every notch.
+                        self.adjust_font_size(FONT_SIZE_STEP * scroll.signum(), false);
+                        self.font_size_dirty = true;
+                    }
+                    ui.painter().rect_stroke(
+                        ui.max_rect(),
+                        0.0,
+                        eframe::egui::Stroke::new(2.0, self.style.text_color),
+                        eframe::egui::StrokeKind::Inside,
+                    );
+                } else if let Some(start) = self.move_start.take() {
+                    if std::mem::take(&mut self.font_size_dirty) {
+                        self.persist_font_size();
+                    }
+                    let resized = self.size_start.take().zip(inner_size).filter(|(before, after)| before != after);
+                    if let Some((_, size)) = resized {
+                        match save_window_size(&self.config_path, (size.x, size.y)) {
+                            Ok(()) => log::info!("Saved window size {:.1} x {:.1}", size.x, size.y),
+                            Err(e) => log::warn!("Failed to save window size to {}: {}", self.config_path, e),
+                        }
+                        // The anchor point depends on the size, and window_offset is relative to it.
+                        if let Some(settings) = &self.active_settings {
+                            let monitor = get_monitor_rect(settings.monitor_index());
+                            self.anchor_origin = settings.anchor_origin(&monitor, size.x, size.y);
+                        }
+                    }
+                    if let Some(pos) = outer_pos.filter(|pos| *pos != start || resized.is_some()) {
+                        let offset = (pos.x - self.anchor_origin.0, pos.y - self.anchor_origin.1);
+                        match save_window_offset(&self.config_path, offset) {
+                            Ok(()) => log::info!("Saved window_offset = [{:.1}, {:.1}]", offset.0, offset.1),
+                            Err(e) => log::warn!("Failed to save window_offset to {}: {}", self.config_path, e),
+                        }
+                    }
+                }
                 if !self.initialized_windows {
-                    initialize_window(frame);
+                    initialize_window(frame, self.z_order);
                     self.initialized_windows = true;
                 }
                 if self.enable_high_priority {
-                    initialize_tool_window(frame);
+                    initialize_tool_window(frame, self.z_order);
                 }
                 if let Ok(transcription) = self.rx_transcription.try_recv() {
                     self.mode.handle_incoming(&mut self.subtitles_state, transcription);
@@ -156,14 +700,88 @@ impl App for SubtitlesApp {
                 if self.subtitles_state.update_animation(self.mode.as_ref()) {
                     ctx.request_repaint();
                 }
+                for lane in &mut self.target_lanes {
+                    if let Ok(transcription) = lane.rx_transcription.try_recv() {
+                        self.mode.handle_incoming(&mut lane.state, transcription);
+                        ctx.request_repaint();
+                    }
+                    if lane.state.update_animation(self.mode.as_ref()) {
+                        ctx.request_repaint();
+                    }
+                }
+                self.write_obs_text_file();
+
+                while let Ok(action) = self.rx_hotkey.try_recv() {
+                    match action {
+                        HotkeyAction::TogglePause => self.toggle_pause(),
+                        HotkeyAction::FontLarger => self.adjust_font_size(FONT_SIZE_STEP, true),
+                        HotkeyAction::FontSmaller => self.adjust_font_size(-FONT_SIZE_STEP, true),
+                        HotkeyAction::ToggleDebugWindow => {
+                            self.debug_window_open = !self.debug_window_open;
+                            ctx.request_repaint();
+                        }
+                        HotkeyAction::ClearBuffer => self.clear_captions(ctx),
+                        HotkeyAction::CommitInterim => self.commit_interim(ctx),
+                    }
+                }
+
+                if let Ok(notice) = self.rx_notice.try_recv() {
+                    self.notice = Some((notice, Instant::now()));
+                }
+                if let Some((notice, shown_at)) = &self.notice {
+                    if shown_at.elapsed() < NOTICE_DURATION {
+                        draw_notice(ui, notice, self.style.font_size * 0.6);
+                    } else {
+                        self.notice = None;
+                    }
+                }
+
+                while let Ok(status) = self.rx_status.try_recv() {
+                    self.stream_status = Some(status);
+                }
+                if let Some(status) = self.stream_status {
+                    let visible = match self.status_indicator {
+                        StatusIndicator::Always => true,
+                        StatusIndicator::Problems => status != StreamStatus::Live,
+                        StatusIndicator::Never => false,
+                    };
+                    if visible {
+                        draw_status_dot(ui, self.status_corner, status);
+                    }
+                }
 
+                for speaker in self.subtitles_state.iter().filter_map(|line| line.speaker.as_ref()) {
+                    if !self.speaker_colors.contains_key(speaker) {
+                        let color = SPEAKER_PALETTE[self.speaker_colors.len() % SPEAKER_PALETTE.len()];
+                        self.speaker_colors.insert(speaker.clone(), color);
+                    }
+                }
+
+                if self.paused {
+                    draw_paused(ui, self.style.font_size, self.style.text_color);
+                    ctx.request_repaint_after(POLL_INTERVAL);
+                    return;
+                }
+
+                // With extra target languages the window is split into equal bands, main captions on top.
+                let window = ctx.content_rect();
+                let band_height = window.height() / (self.target_lanes.len() + 1) as f32;
+                let band = |index: usize| {
+                    Rect::from_min_size(
+                        Pos2::new(window.left(), window.top() + band_height * index as f32),
+                        Vec2::new(window.width(), band_height),
+                    )
+                };
                 ui.vertical(|ui| {
                     let target_height = draw_text_with_shadow(
                         ui,
+                        band(0),
                         self.subtitles_state.iter(),
-                        self.font_size,
-                        self.text_color,
+                        &self.style,
+                        &self.font_size_overrides,
+                        &self.speaker_colors,
                         self.interim_current_height,
+                        self.subtitles_state.silence_opacity(),
                     );
                     
                     // Smoothly animate towards target height
@@ -177,6 +795,20 @@ impl App for SubtitlesApp {
                     } else {
                         self.interim_current_height = target_height;
                     }
+
+                    for (index, lane) in self.target_lanes.iter().enumerate() {
+                        let style = TextStyle { text_color: TARGET_PALETTE[index % TARGET_PALETTE.len()], ..self.style };
+                        draw_text_with_shadow(
+                            ui,
+                            band(index + 1),
+                            lane.state.iter(),
+                            &style,
+                            &self.font_size_overrides,
+                            &self.speaker_colors,
+                            0.0,
+                            lane.state.silence_opacity(),
+                        );
+                    }
                 });
                 
                 // Ensure we poll for new data even if no events come in
@@ -185,7 +817,17 @@ impl App for SubtitlesApp {
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // End the audio first, then wait for Soniox to finalize and close so the last utterance
+        // still reaches the transcript and export. The stream runs on the runtime's worker threads.
         let _ = self.tx_audio.send(AudioMessage::Stop);
+        if let Err(RecvTimeoutError::Timeout) = self.rx_stream_done.recv_timeout(SHUTDOWN_TIMEOUT) {
+            log::warn!("Soniox stream did not finish within {:?}; exiting anyway", SHUTDOWN_TIMEOUT);
+        }
+        while let Ok(transcription) = self.rx_transcription.try_recv() {
+            self.mode.handle_incoming(&mut self.subtitles_state, transcription);
+        }
+        self.subtitles_state.flush_pending_events(self.mode.as_ref());
+        self.subtitles_state.finish_export();
         let _ = self.tx_exit.send(true);
         self.rx_transcription.close();
     }
@@ -194,3 +836,28 @@ impl App for SubtitlesApp {
         [0.0, 0.0, 0.0, 0.0]
     }
 }
+
+/// The caption-buffer settings that apply live, shared by the main captions and target lanes.
+fn apply_state_settings(state: &mut TranscriptionState, settings: &SettingsApp, word_filter: &[String]) {
+    state.set_stability_params(settings.show_interim(), settings.stability_timeout_ms());
+    state.set_freeze_mode(settings.freeze_mode());
+    state.set_smart_delay(settings.smart_delay_ms());
+    state.set_clear_after_silence(settings.clear_after_silence_ms());
+    state.set_min_line_display(settings.min_line_display_ms());
+    state.set_animation_speed(settings.animation_speed_ms(), settings.reveal_mode());
+    state.set_confidence_thresholds(settings.min_confidence(), settings.drop_confidence());
+    state.set_speaker_blocks(settings.enable_speaker_blocks());
+    state.set_timestamps(settings.timestamps());
+    state.set_word_filter(word_filter);
+}
+
+fn config_modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Writes a temp file next to `path` and renames it over, so readers never see a partial file.
+fn write_atomically(path: &str, contents: &str) -> std::io::Result<()> {
+    let temp = format!("{}.tmp", path);
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path)
+}
@@ -1,24 +1,190 @@
+use crate::soniox::state::ends_sentence;
 use crate::types::audio::AudioSubtitle;
-use eframe::egui::{Ui, pos2, vec2};
+use crate::types::languages::LanguageHint;
+use crate::types::settings::{FlowDirection, TextAlign, TextDirection};
+use crate::types::soniox::StreamStatus;
+use eframe::egui::{Align, Align2, Pos2, Rect, Ui, pos2, vec2};
+use eframe::epaint::TextShape;
+use eframe::epaint::text::{Galley, LayoutJob, TextFormat};
 use eframe::epaint::{Color32, FontId};
+use std::collections::HashMap;
+use std::sync::Arc;
 
+/// Size of the detected-language tag relative to the subtitle font.
+const LANGUAGE_TAG_SCALE: f32 = 0.6;
+/// Opacity of interim text whose confidence is below `min_confidence`.
+const LOW_CONFIDENCE_DIM: f32 = 0.5;
+/// Size and dimming of the original-speech line above a translation.
+const SOURCE_TEXT_SCALE: f32 = 0.75;
+const NOTICE_COLOR: Color32 = Color32::from_rgb(255, 90, 90);
+/// Radius of the connection status dot, and its distance from the window edges, in points.
+const STATUS_DOT_RADIUS: f32 = 5.0;
+const STATUS_DOT_MARGIN: f32 = 6.0;
+
+/// Background panel padding around each line, in points.
+const BACKGROUND_PADDING: f32 = 6.0;
+/// With `fade_old_lines`, lines fade out across this top fraction of the window.
+const FADE_ZONE: f32 = 0.35;
+
+/// How subtitle lines are painted.
+#[derive(Clone, Copy)]
+pub(crate) struct TextStyle {
+    pub(crate) font_size: f32,
+    pub(crate) text_color: Color32,
+    pub(crate) show_language_tags: bool,
+    pub(crate) show_speaker_labels: bool,
+    /// Fill of the rounded panel drawn behind each line, if enabled.
+    pub(crate) background: Option<Color32>,
+    pub(crate) align: TextAlign,
+    /// Right-to-left lines mirror the alignment and put the speaker label after the text.
+    pub(crate) direction: TextDirection,
+    /// Fade lines out as they approach the top instead of clipping them.
+    pub(crate) fade_old_lines: bool,
+    pub(crate) flow: FlowDirection,
+    /// Share of the window width lines wrap at; SubtitlesApp derives max_chars from the same value.
+    pub(crate) wrap_width_ratio: f32,
+    /// Original speech in the left half and each translated block level with it in the right half.
+    pub(crate) side_by_side: bool,
+}
+
+impl TextStyle {
+    /// Width lines wrap at in a window `width` points wide; with `side_by_side`, each column is half of it.
+    pub(crate) fn wrap_width(&self, width: f32) -> f32 {
+        let column = if self.side_by_side { width / 2.0 } else { width };
+        column * self.wrap_width_ratio
+    }
+}
+
+/// Whether `line` reads right to left under `direction`.
+fn is_rtl_line(line: &AudioSubtitle, direction: TextDirection) -> bool {
+    match direction {
+        TextDirection::Ltr => false,
+        TextDirection::Rtl => true,
+        TextDirection::Auto => match line.language {
+            Some(language) => language.is_rtl(),
+            None => line.text.chars().find(|c| c.is_alphabetic()).is_some_and(is_rtl_char),
+        },
+    }
+}
+
+/// Whether the original speech `source` reads right to left; its language isn't kept, so `Auto` goes by the script.
+fn is_rtl_source(source: &str, direction: TextDirection) -> bool {
+    match direction {
+        TextDirection::Ltr => false,
+        TextDirection::Rtl => true,
+        TextDirection::Auto => source.chars().find(|c| c.is_alphabetic()).is_some_and(is_rtl_char),
+    }
+}
+
+/// Hebrew and Arabic-script letters (Arabic, Persian, Urdu), including presentation forms.
+fn is_rtl_char(c: char) -> bool {
+    matches!(c, '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}')
+}
+
+/// Lays out one block: the original speech above a translation if kept and `show_source`, an optional small
+/// language tag, the speaker prefix and the text.
+/// For right-to-left lines the tag and speaker label go after the text instead, on the reading-start side.
+/// egui doesn't reorder bidirectional text, so glyphs still run in logical order.
+/// With `dim_color`, low-confidence runs of the text are drawn in that color.
+fn layout_line(line: &AudioSubtitle, font_size: f32, color: Color32, dim_color: Option<Color32>, show_language_tag: bool, show_speaker_label: bool, show_source: bool, wrap_width: f32, halign: Align, rtl: bool) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    job.wrap.max_width = wrap_width;
+    // Each wrapped row is aligned on its own, so centered text stays balanced as lines change width.
+    job.halign = halign;
+
+    let font = FontId::proportional(font_size);
+    let tag = line.language.filter(|_| show_language_tag).map(|language| language.code());
+    let speaker = line.speaker.as_ref().filter(|_| show_speaker_label);
+    let tag_format = TextFormat::simple(FontId::proportional(font_size * LANGUAGE_TAG_SCALE), color);
+
+    if let Some(source) = line.source_text.as_deref().map(str::trim).filter(|source| show_source && !source.is_empty()) {
+        let source_format = TextFormat::simple(FontId::proportional(font_size * SOURCE_TEXT_SCALE), color.gamma_multiply(LOW_CONFIDENCE_DIM));
+        job.append(&format!("{}\n", source), 0.0, source_format);
+    }
+
+    if !rtl {
+        if let Some(timestamp) = &line.timestamp {
+            job.append(&format!("{} ", timestamp), 0.0, tag_format.clone());
+        }
+        if let Some(code) = tag {
+            job.append(&format!("[{}] ", code), 0.0, tag_format.clone());
+        }
+        if let Some(speaker) = speaker {
+            job.append(&format!("{} >> ", speaker), 0.0, TextFormat::simple(font.clone(), color));
+        }
+    }
+
+    let text = line.displayed_text.as_str();
+    let mut cursor = 0;
+    if let Some(dim_color) = dim_color {
+        for range in &line.low_confidence {
+            let start = range.start.max(cursor).min(text.len());
+            let end = range.end.min(text.len());
+            if start >= end || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                continue;
+            }
+            job.append(&text[cursor..start], 0.0, TextFormat::simple(font.clone(), color));
+            job.append(&text[start..end], 0.0, TextFormat::simple(font.clone(), dim_color));
+            cursor = end;
+        }
+    }
+    job.append(&text[cursor..], 0.0, TextFormat::simple(font.clone(), color));
+
+    if rtl {
+        if let Some(speaker) = speaker {
+            job.append(&format!(" << {}", speaker), 0.0, TextFormat::simple(font, color));
+        }
+        if let Some(code) = tag {
+            job.append(&format!(" [{}]", code), 0.0, tag_format.clone());
+        }
+        if let Some(timestamp) = &line.timestamp {
+            job.append(&format!(" {}", timestamp), 0.0, tag_format);
+        }
+    }
+    job
+}
+
+/// The original speech of a block as its own column of the side-by-side layout, at full size.
+fn layout_source(source: &str, font_size: f32, color: Color32, wrap_width: f32, halign: Align) -> LayoutJob {
+    let mut job = LayoutJob::simple(source.to_string(), FontId::proportional(font_size), color, wrap_width);
+    job.halign = halign;
+    job
+}
+
+/// Draws the caption stack inside `rect`, clipped to it; the overlay passes its whole content
+/// rect, or one band of it per target language.
 pub(crate) fn draw_text_with_shadow<'a>(
     ui: &mut Ui,
+    rect: Rect,
     lines: impl Iterator<Item = &'a AudioSubtitle>,
-    font_size: f32,
-    text_color: Color32,
+    style: &TextStyle,
+    font_size_overrides: &HashMap<LanguageHint, f32>,
+    speaker_colors: &HashMap<String, Color32>,
     _interim_visual_height: f32,
+    opacity: f32,
 ) -> f32 {
-    let font = FontId::proportional(font_size);
-    let painter = ui.painter();
-    let rect = ui.ctx().content_rect();
+    let TextStyle { font_size, text_color, show_language_tags, show_speaker_labels, background, align, direction, fade_old_lines, flow, side_by_side, .. } = *style;
+    let painter = ui.painter().with_clip_rect(rect);
     let outline_color = Color32::BLACK;
     let thickness = 2.0;
     
     // Start from the bottom with some padding
     // let mut current_y = rect.bottom() - 10.0; // This line is removed
-    let available_width = rect.width() * 0.8; // Use 80% of width
-    let start_x = rect.left() + 10.0;
+    let available_width = style.wrap_width(rect.width());
+    // Side by side, the original speech takes the left half and the translation the right half.
+    let (source_column, text_column) = if side_by_side {
+        let middle = rect.center().x;
+        (Rect::from_min_max(rect.min, pos2(middle, rect.bottom())), Rect::from_min_max(pos2(middle, rect.top()), rect.max))
+    } else {
+        (rect, rect)
+    };
+    // Galleys are laid out around their anchor: left edge, center, or right edge of their column.
+    // Right-to-left lines mirror the alignment, so "left" means the reading start for every script.
+    let anchor = |column: Rect, rtl: bool| match (align, rtl) {
+        (TextAlign::Left, false) | (TextAlign::Right, true) => (column.left() + 10.0, Align::LEFT),
+        (TextAlign::Center, _) => (column.center().x, Align::Center),
+        (TextAlign::Right, false) | (TextAlign::Left, true) => (column.right() - 10.0, Align::RIGHT),
+    };
 
     // let mut first_item_height = 0.0; // This line is removed
 
@@ -36,29 +202,32 @@ pub(crate) fn draw_text_with_shadow<'a>(
     let mut layouts = Vec::with_capacity(render_blocks.len());
 
     for (index, line) in render_blocks.iter().enumerate() {
-        let mut text = String::new();
-        if let Some(speaker) = &line.speaker {
-            text.push_str(&format!("{} >> ", speaker));
-        }
-        text.push_str(&line.displayed_text);
-
-        let galley = painter.layout(
-            text.clone(),
-            font.clone(),
-            text_color,
-            available_width,
-        );
-        
-        let shadow_galley = painter.layout(
-            text,
-            font.clone(),
-            outline_color,
-            available_width,
-        );
-        
+        // Speakers get their own tint; lines without a speaker use the configured color.
+        let fill = line
+            .speaker
+            .as_ref()
+            .and_then(|speaker| speaker_colors.get(speaker))
+            .copied()
+            .unwrap_or(text_color);
+        let dim_color = fill.gamma_multiply(LOW_CONFIDENCE_DIM);
+        // The size hotkeys only change the base size; a language with its own size keeps it.
+        let font_size = line.language.and_then(|language| font_size_overrides.get(&language)).copied().unwrap_or(font_size);
+        let rtl = is_rtl_line(line, direction);
+        let (start_x, halign) = anchor(text_column, rtl);
+        let show_source = !side_by_side;
+        let galley = painter.layout_job(layout_line(line, font_size, fill, Some(dim_color), show_language_tags, show_speaker_labels, show_source, available_width, halign, rtl));
+        let shadow_galley = painter.layout_job(layout_line(line, font_size, outline_color, None, show_language_tags, show_speaker_labels, show_source, available_width, halign, rtl));
+        // The original speech shares the block's row, so both columns stay level.
+        let source = line.source_text.as_deref().map(str::trim).filter(|source| side_by_side && !source.is_empty()).map(|source| {
+            let (source_x, halign) = anchor(source_column, is_rtl_source(source, direction));
+            let galley = painter.layout_job(layout_source(source, font_size, fill, available_width, halign));
+            let shadow_galley = painter.layout_job(layout_source(source, font_size, outline_color, available_width, halign));
+            (galley, shadow_galley, source_x)
+        });
+
         // Double line break after sentences
-        let ends_sentence = line.text.trim_end().ends_with(|c| c == '.' || c == '?' || c == '!');
-        let height = galley.size().y;
+        let ends_sentence = ends_sentence(&line.text);
+        let height = galley.size().y.max(source.as_ref().map_or(0.0, |(galley, _, _)| galley.size().y));
         let mut block_spacing = 0.0;
         
         // Add spacing if it ends a sentence AND it's not the very last block (interim usually doesn't end with punctuation anyway)
@@ -67,35 +236,97 @@ pub(crate) fn draw_text_with_shadow<'a>(
         }
 
         total_height += height + block_spacing;
-        layouts.push((galley, shadow_galley, height, block_spacing));
+        layouts.push((galley, shadow_galley, source, fill, start_x, height, block_spacing));
     }
 
-    // Second pass: Render anchored at the bottom
-    let mut current_y = rect.bottom() - 10.0 - total_height;
+    // Second pass: Render anchored at the bottom, or at the top for top-down flow.
+    // Top-down still scrolls once the stack is taller than the window, keeping the newest line visible.
+    let bottom_anchored_y = rect.bottom() - 10.0 - total_height;
+    let mut current_y = match flow {
+        FlowDirection::Up => bottom_anchored_y,
+        FlowDirection::Down => (rect.top() + 10.0).min(bottom_anchored_y),
+    };
     
     let mut last_block_height = 0.0;
+    let block_count = layouts.len();
+    let fade_height = rect.height() * FADE_ZONE;
+
+    // Shadow offsets, drawn under the text as an outline.
+    let offsets = [
+        vec2(-thickness, 0.0), vec2(thickness, 0.0),
+        vec2(0.0, -thickness), vec2(0.0, thickness),
+        vec2(-thickness, -thickness), vec2(-thickness, thickness),
+        vec2(thickness, -thickness), vec2(thickness, thickness),
+    ];
+    let paint = |pos: Pos2, galley: Arc<Galley>, shadow_galley: Arc<Galley>, fill: Color32, alpha: f32| {
+        // Panel behind the text, hugging the wrapped galley and clipped to the window
+        if let Some(background) = background {
+            let panel = galley.rect.translate(pos.to_vec2()).expand(BACKGROUND_PADDING).intersect(rect);
+            painter.rect_filled(panel, BACKGROUND_PADDING, background.gamma_multiply(alpha));
+        }
+        for offset in offsets {
+            // Section colors are baked into the galleys, so fading goes through the shape's opacity.
+            painter.add(TextShape::new(pos + offset, shadow_galley.clone(), outline_color).with_opacity_factor(alpha));
+        }
+        painter.add(TextShape::new(pos, galley, fill).with_opacity_factor(alpha));
+    };
 
-    for (galley, shadow_galley, height, spacing) in layouts {
+    for (index, (galley, shadow_galley, source, fill, start_x, height, spacing)) in layouts.into_iter().enumerate() {
         last_block_height = height;
         let pos = pos2(start_x, current_y);
+        current_y += height + spacing;
 
-        // Draw shadow
-        let offsets = [
-            vec2(-thickness, 0.0), vec2(thickness, 0.0),
-            vec2(0.0, -thickness), vec2(0.0, thickness),
-            vec2(-thickness, -thickness), vec2(-thickness, thickness),
-            vec2(thickness, -thickness), vec2(thickness, thickness),
-        ];
-
-        for offset in offsets {
-            painter.galley(pos + offset, shadow_galley.clone(), outline_color);
+        // The newest line always stays fully visible.
+        let position_alpha = if fade_old_lines && index + 1 < block_count {
+            ((pos.y - rect.top()) / fade_height).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let alpha = position_alpha * opacity;
+        if alpha <= 0.0 {
+            continue;
         }
 
-        // Draw main text
-        painter.galley(pos, galley, text_color);
-
-        current_y += height + spacing;
+        if let Some((source_galley, source_shadow, source_x)) = source {
+            paint(pos2(source_x, pos.y), source_galley, source_shadow, fill, alpha);
+        }
+        paint(pos, galley, shadow_galley, fill, alpha);
     }
     
     last_block_height
 }
+
+/// Small pause sign in the top-left corner, shown instead of the captions while paused.
+pub(crate) fn draw_paused(ui: &mut Ui, font_size: f32, color: Color32) {
+    let painter = ui.painter();
+    let rect = ui.ctx().content_rect();
+    let bar = vec2(font_size * 0.2, font_size * 0.7);
+    let origin = pos2(rect.left() + 10.0, rect.top() + 10.0);
+    painter.rect_filled(Rect::from_min_size(origin, bar), 1.0, color);
+    painter.rect_filled(Rect::from_min_size(origin + vec2(bar.x * 2.0, 0.0), bar), 1.0, color);
+}
+
+/// Connection status dot in `corner`: green while live, yellow without audio, red while disconnected.
+pub(crate) fn draw_status_dot(ui: &mut Ui, corner: Align2, status: StreamStatus) {
+    let color = match status {
+        StreamStatus::Live => Color32::from_rgb(80, 200, 80),
+        StreamStatus::NoAudio => Color32::from_rgb(230, 190, 40),
+        StreamStatus::Disconnected => Color32::from_rgb(230, 60, 60),
+    };
+    let area = ui.ctx().content_rect().shrink(STATUS_DOT_MARGIN + STATUS_DOT_RADIUS);
+    let painter = ui.painter();
+    let center = corner.pos_in_rect(&area);
+    painter.circle_filled(center, STATUS_DOT_RADIUS + 1.0, Color32::from_black_alpha(160));
+    painter.circle_filled(center, STATUS_DOT_RADIUS, color);
+}
+
+/// Flashes a short stream notice (e.g. a Soniox error) in the top-left corner.
+pub(crate) fn draw_notice(ui: &mut Ui, text: &str, font_size: f32) {
+    let painter = ui.painter();
+    let rect = ui.ctx().content_rect();
+    let pos = pos2(rect.left() + 10.0, rect.top() + 10.0);
+    let galley = painter.layout(text.to_string(), FontId::proportional(font_size), NOTICE_COLOR, rect.width() - 20.0);
+    let background = galley.rect.translate(pos.to_vec2()).expand(4.0);
+    painter.rect_filled(background, 4.0, Color32::from_black_alpha(200));
+    painter.galley(pos, galley, NOTICE_COLOR);
+}
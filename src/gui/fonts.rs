@@ -0,0 +1,68 @@
+use eframe::egui::{FontData, FontDefinitions, FontFamily};
+use std::sync::Arc;
+
+const EMBEDDED_FONT: &str = "mplus";
+const CUSTOM_FONT: &str = "custom";
+
+/// Font setup for the overlay: `font_path` as the primary proportional font when it loads,
+/// otherwise the embedded one. `fallback_fonts` follow in order, so egui picks each missing
+/// glyph from the first font that has it. The embedded font is also the monospace fallback.
+pub fn load_fonts(embedded: &'static [u8], font_path: Option<&str>, fallback_fonts: &[String]) -> FontDefinitions {
+    let mut fonts = FontDefinitions::default();
+    fonts
+        .font_data
+        .insert(EMBEDDED_FONT.to_owned(), Arc::new(FontData::from_static(embedded)));
+
+    let primary = match font_path.map(read_font_file) {
+        Some(Ok(bytes)) => {
+            log::info!("Using font from {}", font_path.unwrap_or_default());
+            fonts
+                .font_data
+                .insert(CUSTOM_FONT.to_owned(), Arc::new(FontData::from_owned(bytes)));
+            CUSTOM_FONT
+        }
+        Some(Err(e)) => {
+            log::warn!("Failed to load font_path, using the embedded font: {}", e);
+            EMBEDDED_FONT
+        }
+        None => EMBEDDED_FONT,
+    };
+
+    let mut chain = vec![primary.to_owned()];
+    if primary != EMBEDDED_FONT {
+        // Still covers CJK for glyphs the custom font lacks.
+        chain.push(EMBEDDED_FONT.to_owned());
+    }
+    for (index, path) in fallback_fonts.iter().enumerate() {
+        match read_font_file(path) {
+            Ok(bytes) => {
+                let name = format!("fallback{}", index);
+                fonts.font_data.insert(name.clone(), Arc::new(FontData::from_owned(bytes)));
+                chain.push(name);
+            }
+            Err(e) => log::warn!("Skipping fallback font: {}", e),
+        }
+    }
+    // egui's own defaults (Latin, emoji) stay at the end of the chain.
+    fonts
+        .families
+        .entry(FontFamily::Proportional)
+        .or_default()
+        .splice(0..0, chain);
+    fonts
+        .families
+        .entry(FontFamily::Monospace)
+        .or_default()
+        .push(EMBEDDED_FONT.to_owned());
+    fonts
+}
+
+/// Reads a TTF/OTF/TTC file, rejecting anything without a font header so egui doesn't panic on it later.
+fn read_font_file(path: &str) -> Result<Vec<u8>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    let is_font = matches!(bytes.get(..4), Some([0, 1, 0, 0] | b"OTTO" | b"true" | b"ttcf"));
+    if !is_font {
+        return Err(format!("{}: not a TrueType/OpenType font", path));
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,135 @@
+use crate::errors::SonioxWindowsErrors;
+use crate::soniox::caption_server::start_caption_server;
+use crate::soniox::state::{FinalSegment, TranscriptionState};
+use crate::soniox::stream::start_soniox_stream;
+use crate::types::audio::AudioMessage;
+use crate::types::settings::SettingsApp;
+use crate::types::soniox::{ControlMsg, SonioxTranscriptionResponse, StreamStatus};
+use crate::windows::audio::start_capture_audio;
+use crate::{capture_options, create_mode, init_logging};
+use std::io::Write;
+use std::time::Duration;
+use tokio::sync::mpsc::unbounded_channel;
+
+/// How often queued responses are applied (smart_delay) when nothing new arrives.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Nothing is drawn, so blocks only need to be short enough for the exporter's cues.
+const MAX_CHARS_IN_BLOCK: usize = 200;
+
+/// Runs capture and the Soniox stream without the overlay, printing finalized text to stdout
+/// one line per sentence (or speaker turn). The transcript file and subtitle export work as
+/// with the overlay. Ctrl+C stops the audio and waits for Soniox to finalize the rest.
+pub async fn run_headless(settings: SettingsApp) -> Result<(), SonioxWindowsErrors> {
+    init_logging(&settings)?;
+    let (tx_audio, rx_audio) = unbounded_channel::<AudioMessage>();
+    let (tx_transcription, mut rx_transcription) = unbounded_channel::<SonioxTranscriptionResponse>();
+    let (tx_exit, rx_exit) = unbounded_channel::<bool>();
+    let (_tx_control, rx_control) = unbounded_channel::<ControlMsg>();
+    let (tx_notice, mut rx_notice) = unbounded_channel::<String>();
+    // Only the overlay draws the status; its receiver is dropped right away.
+    let (tx_status, _) = unbounded_channel::<StreamStatus>();
+    let (tx_final, mut rx_final) = unbounded_channel::<FinalSegment>();
+
+    let mode = create_mode(&settings);
+    let mut state = TranscriptionState::new(settings.max_lines(), MAX_CHARS_IN_BLOCK);
+    state.set_smart_delay(settings.smart_delay_ms());
+    state.set_confidence_thresholds(settings.min_confidence(), settings.drop_confidence());
+    state.set_speaker_blocks(settings.enable_speaker_blocks());
+    state.set_word_filter(&settings.word_filter()?);
+    state.set_logging(settings.save_transcription(), settings.transcript_save_path(), settings.transcript_format());
+    state.set_export(settings.export_format(), settings.transcript_save_path());
+    state.add_final_sink(tx_final);
+    if let Some(port) = settings.caption_server_port() {
+        state.add_final_sink(start_caption_server(port));
+    }
+
+    let capture_options = capture_options(&settings);
+    let tx_capture = tx_audio.clone();
+    let tx_stop = tx_audio.clone();
+    tokio::task::spawn_blocking(move || {
+        if let Err(err) = start_capture_audio(tx_capture, rx_exit, &capture_options) {
+            log::error!("{}", err);
+            eprintln!("Audio capture failed: {}", err);
+            // Ends the stream, as Ctrl+C would, instead of waiting for audio that never comes.
+            let _ = tx_stop.send(AudioMessage::Stop);
+        }
+    });
+    // The stream owns tx_transcription, so the loop below ends once the stream has finished.
+    let stream = tokio::spawn(async move {
+        start_soniox_stream(&settings, settings.target_language(), tx_transcription, tx_notice, tx_status, rx_audio, rx_control).await
+    });
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut stopping = false;
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+    let mut printer = LinePrinter::default();
+    loop {
+        tokio::select! {
+            response = rx_transcription.recv() => match response {
+                Some(response) => mode.handle_incoming(&mut state, response),
+                None => break,
+            },
+            Some(notice) = rx_notice.recv() => eprintln!("{}", notice),
+            _ = poll.tick() => state.process_pending_events(mode.as_ref()),
+            _ = &mut ctrl_c, if !stopping => {
+                log::info!("Ctrl+C received, finishing the stream");
+                stopping = true;
+                let _ = tx_audio.send(AudioMessage::Stop);
+            }
+        }
+        while let Ok(segment) = rx_final.try_recv() {
+            printer.push(&segment);
+        }
+    }
+
+    state.flush_pending_events(mode.as_ref());
+    while let Ok(segment) = rx_final.try_recv() {
+        printer.push(&segment);
+    }
+    printer.finish();
+    state.finish_export();
+    let _ = tx_exit.send(true);
+
+    match stream.await {
+        Ok(result) => result,
+        Err(err) => {
+            log::error!("Soniox stream task failed: {}", err);
+            Ok(())
+        }
+    }
+}
+
+/// Collects final segments (which can end mid-word) and prints whole lines.
+#[derive(Default)]
+struct LinePrinter {
+    line: String,
+    speaker: Option<String>,
+}
+
+impl LinePrinter {
+    fn push(&mut self, segment: &FinalSegment) {
+        if segment.speaker.is_some() && segment.speaker != self.speaker {
+            self.finish();
+            self.speaker = segment.speaker.clone();
+        }
+        self.line.push_str(&segment.text);
+        if self.line.trim_end().ends_with(['.', '!', '?', '。', '！', '？']) {
+            self.finish();
+        }
+    }
+
+    /// Prints whatever is buffered as a line of its own.
+    fn finish(&mut self) {
+        let text = self.line.trim();
+        if !text.is_empty() {
+            let mut stdout = std::io::stdout().lock();
+            let _ = match &self.speaker {
+                Some(speaker) => writeln!(stdout, "[{}] {}", speaker, text),
+                None => writeln!(stdout, "{}", text),
+            };
+            let _ = stdout.flush();
+        }
+        self.line.clear();
+    }
+}
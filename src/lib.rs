@@ -1,18 +1,21 @@
 use crate::errors::SonioxWindowsErrors;
 use crate::gui::app::SubtitlesApp;
+use crate::soniox::caption_server::start_caption_server;
 use crate::soniox::stream::start_soniox_stream;
 use crate::types::audio::AudioMessage;
 use crate::types::settings::SettingsApp;
-use crate::types::soniox::SonioxTranscriptionResponse;
-use crate::windows::audio::start_capture_audio;
+use crate::types::soniox::{ControlMsg, SonioxTranscriptionResponse, StreamStatus};
+use crate::windows::audio::{CaptureOptions, start_capture_audio};
+use crate::windows::utils::{HotkeyAction, get_monitor_rect, show_error, start_hotkey_listener};
 use log4rs::Config;
 use log4rs::append::file::FileAppender;
 use log4rs::config::{Appender, Root};
 use log4rs::encode::pattern::PatternEncoder;
-use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
 
 pub mod errors;
 pub mod gui;
+pub mod headless;
 pub mod soniox;
 pub mod types;
 pub mod windows;
@@ -23,7 +26,7 @@ use crate::soniox::modes::SonioxMode;
 use crate::soniox::transcribe_mode::TranscribeMode;
 use crate::soniox::translate_mode::TranslateMode;
 
-pub fn initialize_app(settings: SettingsApp) -> Result<SubtitlesApp, SonioxWindowsErrors> {
+pub(crate) fn init_logging(settings: &SettingsApp) -> Result<(), SonioxWindowsErrors> {
     let level = settings.level()?;
     let logfile = FileAppender::builder()
         .encoder(Box::new(PatternEncoder::new("{d} - {l} - {m}\n")))
@@ -32,42 +35,159 @@ pub fn initialize_app(settings: SettingsApp) -> Result<SubtitlesApp, SonioxWindo
         .appender(Appender::builder().build("logfile", Box::new(logfile)))
         .build(Root::builder().appender("logfile").build(level))?;
     let _ = log4rs::init_config(config);
+    log::debug!("Soniox API key read from {}", settings.api_key_source());
+    Ok(())
+}
+
+pub(crate) fn create_mode(settings: &SettingsApp) -> Box<dyn SonioxMode + Send + Sync> {
+    if settings.enable_translate() {
+        Box::new(TranslateMode::new(settings.translation_type(), settings.translate_display(), settings.target_language()))
+    } else {
+        Box::new(TranscribeMode)
+    }
+}
+
+pub(crate) fn capture_options(settings: &SettingsApp) -> CaptureOptions {
+    CaptureOptions {
+        input_mode: settings.audio_input().to_string(),
+        enable_audio_logging: settings.enable_audio_logging(),
+        mic_gain: settings.mic_gain(),
+        system_gain: settings.system_gain(),
+        max_system_buffer_ms: settings.max_system_buffer_ms(),
+        capture_mode: settings.capture_mode(),
+        noise_gate_threshold: settings.noise_gate_threshold(),
+        auto_gain: settings.auto_gain(),
+    }
+}
+
+/// Copies every message from `rx` to each of `sinks`, so several streams share one capture.
+fn fan_out<T: Clone + Send + 'static>(mut rx: UnboundedReceiver<T>, sinks: Vec<UnboundedSender<T>>) {
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            for sink in &sinks {
+                let _ = sink.send(message.clone());
+            }
+        }
+    });
+}
+
+pub fn initialize_app(settings: SettingsApp) -> Result<SubtitlesApp, SonioxWindowsErrors> {
+    init_logging(&settings)?;
     let (tx_audio, rx_audio) = unbounded_channel::<AudioMessage>();
     let (tx_transcription, rx_transcription) = unbounded_channel::<SonioxTranscriptionResponse>();
     let (tx_exit, rx_exit) = unbounded_channel::<bool>();
+    let (tx_control, rx_control) = unbounded_channel::<ControlMsg>();
+    let (tx_notice, rx_notice) = unbounded_channel::<String>();
+    let (tx_status, rx_status) = unbounded_channel::<StreamStatus>();
+    let (tx_fatal, rx_fatal) = unbounded_channel::<String>();
+    // Disconnected once every stream task has ended, so the app can wait for the final tokens on exit.
+    let (tx_stream_done, rx_stream_done) = std::sync::mpsc::channel::<()>();
 
-    let mode: Box<dyn SonioxMode + Send + Sync> = if settings.enable_translate() {
-        Box::new(TranslateMode)
-    } else {
-        Box::new(TranscribeMode)
-    };
+    let mode = create_mode(&settings);
 
-    let app = SubtitlesApp::new(
+    let word_filter = settings.word_filter()?;
+    let (font_larger, font_smaller) = settings.font_size_hotkeys();
+    let hotkeys = [
+        (settings.toggle_hotkey(), HotkeyAction::TogglePause),
+        (settings.clear_hotkey(), HotkeyAction::ClearBuffer),
+        (font_larger, HotkeyAction::FontLarger),
+        (font_smaller, HotkeyAction::FontSmaller),
+        (settings.debug_hotkey(), HotkeyAction::ToggleDebugWindow),
+        (settings.commit_hotkey(), HotkeyAction::CommitInterim),
+    ]
+    .into_iter()
+    .filter_map(|(hotkey, action)| Some((hotkey?, action)))
+    .collect();
+    let rx_hotkey = start_hotkey_listener(hotkeys);
+    let monitor = get_monitor_rect(settings.monitor_index());
+    let anchor_origin = settings.anchor_origin(&monitor, settings.window_width(), settings.window_height());
+    let mut app = SubtitlesApp::new(
         rx_transcription,
+        rx_notice,
+        rx_status,
         tx_exit,
         tx_audio.clone(),
-        settings.enable_high_priority(),
-        settings.font_size(),
-        settings.text_color(),
-        settings.show_window_border(),
-        settings.window_width(),
-        settings.debug_window(),
-        settings.show_interim(),
-        settings.stability_timeout_ms(),
-        settings.save_transcription(),
-        settings.transcript_save_path(),
+        tx_control,
+        rx_stream_done,
+        rx_hotkey,
+        rx_fatal,
+        &settings,
+        crate::soniox::validation::cached_realtime_models(settings.api_key()),
+        anchor_origin,
+        &word_filter,
         mode,
     );
-    let audio_input = settings.audio_input().to_string();
-    let enable_audio_logging = settings.enable_audio_logging();
+    if let Some(port) = settings.caption_server_port() {
+        app.add_final_sink(start_caption_server(port));
+    }
+
+    // Soniox translates one-way into a single language, so every extra target runs a session of
+    // its own on copies of the captured audio and of the control messages.
+    let extra_targets = settings.extra_target_languages();
+    let mut extra_streams = Vec::with_capacity(extra_targets.len());
+    let (rx_audio, rx_control) = if extra_targets.is_empty() {
+        (rx_audio, rx_control)
+    } else {
+        let (tx_main_audio, rx_main_audio) = unbounded_channel::<AudioMessage>();
+        let (tx_main_control, rx_main_control) = unbounded_channel::<ControlMsg>();
+        let mut audio_sinks = vec![tx_main_audio];
+        let mut control_sinks = vec![tx_main_control];
+        for language in extra_targets {
+            let (tx_lane_audio, rx_lane_audio) = unbounded_channel::<AudioMessage>();
+            let (tx_lane_control, rx_lane_control) = unbounded_channel::<ControlMsg>();
+            let (tx_lane, rx_lane) = unbounded_channel::<SonioxTranscriptionResponse>();
+            audio_sinks.push(tx_lane_audio);
+            control_sinks.push(tx_lane_control);
+            app.add_target_lane(language, rx_lane, &settings, &word_filter);
+            extra_streams.push((language, tx_lane, rx_lane_audio, rx_lane_control));
+        }
+        fan_out(rx_audio, audio_sinks);
+        fan_out(rx_control, control_sinks);
+        (rx_main_audio, rx_main_control)
+    };
+
+    let settings = std::sync::Arc::new(settings);
+    for (language, tx_lane, rx_lane_audio, rx_lane_control) in extra_streams {
+        let settings = settings.clone();
+        let tx_notice = tx_notice.clone();
+        let tx_stream_done = tx_stream_done.clone();
+        tokio::spawn(async move {
+            // Only the main stream drives the status indicator.
+            let (tx_status, _) = unbounded_channel::<StreamStatus>();
+            let result = start_soniox_stream(&settings, language, tx_lane, tx_notice, tx_status, rx_lane_audio, rx_lane_control).await;
+            if let Err(err) = result {
+                log::error!("Soniox stream for {}: {}", language.code(), err);
+            }
+            drop(tx_stream_done);
+        });
+    }
+
+    let capture_options = capture_options(&settings);
+    let config_path = settings.config_path().to_string();
     tokio::task::spawn_blocking(move || {
-        if let Err(err) = start_capture_audio(tx_audio, rx_exit, &audio_input, enable_audio_logging) {
+        if let Err(err) = start_capture_audio(tx_audio, rx_exit, &capture_options) {
             log::error!("{}", err);
+            // Without audio the overlay would sit there empty; say why, then close it.
+            show_error(&format!(
+                "Audio capture failed:\n{}\n\nCheck audio_input in {}. SoniLiveText will close.",
+                err, config_path
+            ));
+            let _ = tx_fatal.send(err.to_string());
         }
     });
     tokio::spawn(async move {
-        if let Err(err) = start_soniox_stream(&settings, tx_transcription, rx_audio).await {
+        let result =
+            start_soniox_stream(&settings, settings.target_language(), tx_transcription, tx_notice, tx_status, rx_audio, rx_control).await;
+        drop(tx_stream_done);
+        if let Err(err) = result {
             log::error!("{}", err);
+            let msg = match &err {
+                SonioxWindowsErrors::SonioxAuth(reason) => {
+                    format!("Invalid API key.\nSoniox said: {}\n\nCheck the key in {}.", reason, settings.api_key_source())
+                }
+                _ => format!("Lost connection to Soniox:\n{}", err),
+            };
+            let _ = tokio::task::spawn_blocking(move || show_error(&msg)).await;
         }
     });
 
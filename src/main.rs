@@ -1,21 +1,100 @@
 #![windows_subsystem = "windows"]
 
 use eframe::egui::ViewportBuilder;
-use eframe::egui::{FontData, FontDefinitions, FontFamily};
 use eframe::icon_data::from_png_bytes;
 use sonilivetext::errors::SonioxWindowsErrors;
+use sonilivetext::gui::fonts::load_fonts;
 use sonilivetext::gui::utils::get_inner_size;
+use sonilivetext::headless::run_headless;
 use sonilivetext::initialize_app;
-use sonilivetext::types::settings::SettingsApp;
-use sonilivetext::windows::utils::{get_screen_size, show_error};
-use std::sync::Arc;
+use sonilivetext::types::settings::{CONFIG_PATH, SettingsApp, ZOrder};
+use sonilivetext::windows::utils::{get_monitor_rect, show_error};
 
 const FONT_BYTES: &[u8] = include_bytes!("../assets/MPLUSRounded1c-Medium.ttf");
 const ICON_BYTES: &[u8] = include_bytes!("../assets/icon.png");
+/// `--project <name>` loads `projects/<name>.toml`.
+const PROJECTS_DIR: &str = "projects";
+/// Smallest overlay size with `lock_window_size = false`, in points.
+const MIN_WINDOW_SIZE: (f32, f32) = (200.0, 60.0);
+const USAGE: &str = "Usage: sonilivetext [--project <name | path.toml>] [--headless] [--list]";
 
-async fn run() -> Result<(), SonioxWindowsErrors> {
-    let settings = SettingsApp::new("config.toml")?;
-    let (width, height) = get_screen_size();
+enum Command {
+    /// Start the overlay with this config file.
+    Run(String),
+    /// Transcribe with this config file without the overlay, printing to stdout.
+    Headless(String),
+    /// Print the projects in PROJECTS_DIR.
+    List,
+}
+
+fn parse_args() -> Result<Command, String> {
+    let mut args = std::env::args().skip(1);
+    let mut config_path = CONFIG_PATH.to_string();
+    let mut headless = false;
+    let mut list = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--project" => {
+                let project = args.next().ok_or_else(|| format!("--project needs a name or path\n{}", USAGE))?;
+                config_path = project_path(&project);
+            }
+            "--headless" => headless = true,
+            "--list" => list = true,
+            other => return Err(format!("Unknown argument `{}`\n{}", other, USAGE)),
+        }
+    }
+    Ok(if list {
+        Command::List
+    } else if headless {
+        Command::Headless(config_path)
+    } else {
+        Command::Run(config_path)
+    })
+}
+
+/// A `.toml` path is used as is; anything else names a file in PROJECTS_DIR.
+fn project_path(project: &str) -> String {
+    if project.ends_with(".toml") {
+        project.to_string()
+    } else {
+        format!("{}/{}.toml", PROJECTS_DIR, project)
+    }
+}
+
+/// This is a GUI-subsystem binary, so borrow the console of whoever started us to print.
+fn attach_parent_console() {
+    unsafe {
+        let _ = windows::Win32::System::Console::AttachConsole(windows::Win32::System::Console::ATTACH_PARENT_PROCESS);
+    }
+}
+
+fn list_projects() {
+    attach_parent_console();
+    let mut projects: Vec<String> = std::fs::read_dir(PROJECTS_DIR)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().to_str()?.strip_suffix(".toml").map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    projects.sort();
+    if projects.is_empty() {
+        println!("No projects in {}/", PROJECTS_DIR);
+    }
+    let width = projects.iter().map(|project| project.chars().count()).max().unwrap_or(0);
+    for project in projects {
+        // Only the first line of the notes fits the listing.
+        let settings = SettingsApp::new(&project_path(&project)).ok();
+        match settings.as_ref().and_then(|s| s.notes()).and_then(|notes| notes.lines().next()) {
+            Some(note) => println!("{:<width$}  {}", project, note, width = width),
+            None => println!("{}", project),
+        }
+    }
+}
+
+async fn run(config_path: &str) -> Result<(), SonioxWindowsErrors> {
+    let settings = SettingsApp::new(config_path)?;
     
     if let Err(msg) = settings.validate() {
         show_error(&msg);
@@ -23,15 +102,17 @@ async fn run() -> Result<(), SonioxWindowsErrors> {
         std::process::exit(1);
     }
 
-    // Validate model (BLOCKING)
-    if let Err(e) = sonilivetext::soniox::validation::validate_model(&settings) {
-        log::error!("Model validation failed: {}", e);
+    // Validate languages, then model (BLOCKING)
+    let validation = sonilivetext::soniox::validation::validate_languages(&settings)
+        .and_then(|_| sonilivetext::soniox::validation::validate_model(&settings));
+    if let Err(e) = validation {
+        log::error!("Validation failed: {}", e);
         
         use windows::core::w;
         use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_OK, MB_ICONERROR};
 
         unsafe {
-            let msg = format!("Configuration Error:\n{}\n\nPlease check config.toml and try again.", e);
+            let msg = format!("Configuration Error:\n{}\n\nPlease check {} and try again.", e, settings.config_path());
             
             // Convert to UTF-16 for Windows API
             let wide_msg: Vec<u16> = msg.encode_utf16().chain(std::iter::once(0)).collect();
@@ -46,6 +127,8 @@ async fn run() -> Result<(), SonioxWindowsErrors> {
         std::process::exit(1);
     }
 
+    let monitor = get_monitor_rect(settings.monitor_index());
+    log::info!("Monitor {}x{} at {:.0}% scaling", monitor.width, monitor.height, monitor.scale * 100.0);
     let window_width = settings.window_width();
     let window_height = settings.window_height();
     
@@ -53,7 +136,7 @@ async fn run() -> Result<(), SonioxWindowsErrors> {
     let (final_w, final_h) = get_inner_size(
         // screen width needed? Actually now we have specific width.
         // But get_inner_size might handle height default.
-        width as f32, // potentially unused if we passed width directly to it, but let's check utils modification plan
+        monitor.in_points().width, // potentially unused if we passed width directly to it, but let's check utils modification plan
         Some(window_width),
         Some(window_height),
     );
@@ -65,25 +148,37 @@ async fn run() -> Result<(), SonioxWindowsErrors> {
     // So default width was roughly screen_width - OFFSET*4.
     
     // For now, let's call get_position.
-    let position = settings.get_position(width as f32, height as f32, final_w, final_h);
+    let position = settings.get_position(&monitor, final_w, final_h);
     
     // Re-calculate size if needed? No, size is fixed/resolved.
     // But get_inner_size might need the FINAL position if we keep the "dynamic width" logic based on margins.
     // Let's look at get_inner_size again.
     
+    let lock_window_size = settings.lock_window_size();
+    let topmost = settings.z_order() == ZOrder::Topmost;
+    let font_path = settings.font_path().map(str::to_string);
+    let fallback_fonts = settings.fallback_fonts().to_vec();
     let app = initialize_app(settings)?;
-    
-    let native_options = eframe::NativeOptions {
-        viewport: ViewportBuilder::default()
-            .with_app_id("sublive")
-            .with_icon(from_png_bytes(ICON_BYTES).expect("Failed to load icon"))
-            .with_decorations(false)
-            .with_always_on_top()
-            .with_transparent(true)
+
+    let mut viewport = ViewportBuilder::default()
+        .with_app_id("sublive")
+        .with_icon(from_png_bytes(ICON_BYTES).expect("Failed to load icon"))
+        .with_decorations(false)
+        .with_transparent(true)
+        .with_inner_size((final_w, final_h))
+        .with_position(position);
+    if topmost {
+        viewport = viewport.with_always_on_top();
+    }
+    viewport = if lock_window_size {
+        viewport
             .with_min_inner_size((final_w, final_h))
-            .with_inner_size((final_w, final_h))
             .with_max_inner_size((final_w, final_h))
-            .with_position(position),
+    } else {
+        viewport.with_resizable(true).with_min_inner_size(MIN_WINDOW_SIZE)
+    };
+    let native_options = eframe::NativeOptions {
+        viewport,
         ..Default::default()
     };
 
@@ -92,22 +187,7 @@ async fn run() -> Result<(), SonioxWindowsErrors> {
         "Subtitles Live",
         native_options,
         Box::new(move |cc| {
-            let mut fonts = FontDefinitions::default();
-            fonts.font_data.insert(
-                "mplus".to_owned(),
-                Arc::new(FontData::from_static(FONT_BYTES)),
-            );
-            fonts
-                .families
-                .entry(FontFamily::Proportional)
-                .or_default()
-                .insert(0, "mplus".to_owned());
-            fonts
-                .families
-                .entry(FontFamily::Monospace)
-                .or_default()
-                .push("mplus".to_owned());
-            cc.egui_ctx.set_fonts(fonts);
+            cc.egui_ctx.set_fonts(load_fonts(FONT_BYTES, font_path.as_deref(), &fallback_fonts));
             Ok(Box::new(app))
         }),
     )?;
@@ -115,9 +195,38 @@ async fn run() -> Result<(), SonioxWindowsErrors> {
     Ok(())
 }
 
+async fn headless(config_path: &str) -> Result<(), String> {
+    let settings = SettingsApp::new(config_path).map_err(|e| e.to_string())?;
+    settings.validate()?;
+    sonilivetext::soniox::validation::validate_languages(&settings)
+        .and_then(|_| sonilivetext::soniox::validation::validate_model(&settings))
+        .map_err(|e| format!("Configuration Error:\n{}\n\nPlease check {} and try again.", e, settings.config_path()))?;
+    run_headless(settings).await.map_err(|e| e.to_string())
+}
+
 #[tokio::main]
 async fn main() {
-    if let Err(err) = run().await {
+    let config_path = match parse_args() {
+        Ok(Command::Run(path)) => path,
+        Ok(Command::Headless(path)) => {
+            attach_parent_console();
+            if let Err(msg) = headless(&path).await {
+                eprintln!("{}", msg);
+                log::error!("{}", msg);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Ok(Command::List) => {
+            list_projects();
+            return;
+        }
+        Err(msg) => {
+            show_error(&msg);
+            std::process::exit(2);
+        }
+    };
+    if let Err(err) = run(&config_path).await {
         show_error(&format!("{}", err));
         log::error!("error in sonilivetext!: {:?}", err);
         std::process::exit(1);
@@ -0,0 +1,84 @@
+use crate::soniox::state::FinalSegment;
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tungstenite::Message;
+
+/// Captions a client may fall behind by before it skips ahead.
+const CLIENT_BACKLOG: usize = 64;
+
+/// Serves finalized captions on `ws://127.0.0.1:<port>` as one JSON text message per segment:
+/// `{speaker, text, t_start_ms, t_end_ms}`, timed from the start of the first session so they
+/// keep increasing across reconnects. Returns the sink to register on the TranscriptionState.
+pub(crate) fn start_caption_server(port: u16) -> UnboundedSender<FinalSegment> {
+    let (tx_final, mut rx_final) = unbounded_channel::<FinalSegment>();
+    let (tx_clients, _) = broadcast::channel::<String>(CLIENT_BACKLOG);
+
+    let tx_broadcast = tx_clients.clone();
+    tokio::spawn(async move {
+        while let Some(segment) = rx_final.recv().await {
+            let record = serde_json::json!({
+                "speaker": segment.speaker,
+                "text": segment.text.trim(),
+                "t_start_ms": segment.start_ms,
+                "t_end_ms": segment.end_ms,
+            });
+            // Only fails while no client is connected.
+            let _ = tx_broadcast.send(record.to_string());
+        }
+    });
+
+    tokio::spawn(async move {
+        let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("Caption server could not listen on port {}: {}", port, e);
+                return;
+            }
+        };
+        log::info!("Caption server listening on ws://127.0.0.1:{}", port);
+        loop {
+            match listener.accept().await {
+                Ok((stream, addr)) => {
+                    log::info!("Caption client connected from {}", addr);
+                    tokio::spawn(serve_client(stream, tx_clients.subscribe()));
+                }
+                Err(e) => log::warn!("Caption server accept failed: {}", e),
+            }
+        }
+    });
+
+    tx_final
+}
+
+async fn serve_client(stream: TcpStream, mut rx_captions: broadcast::Receiver<String>) {
+    let ws = match tokio_tungstenite::accept_async(stream).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            log::warn!("Caption client handshake failed: {}", e);
+            return;
+        }
+    };
+    let (mut write, mut read) = ws.split();
+    loop {
+        tokio::select! {
+            caption = rx_captions.recv() => match caption {
+                Ok(caption) => {
+                    if write.send(Message::Text(caption.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => log::warn!("Caption client fell behind, skipped {} captions", skipped),
+                Err(RecvError::Closed) => break,
+            },
+            // Nothing is expected from clients; reading keeps pings answered and notices a close.
+            incoming = read.next() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    log::info!("Caption client disconnected");
+}
@@ -0,0 +1,142 @@
+use crate::soniox::state::ends_sentence;
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// A cue is closed once it gets this long, even mid-sentence.
+const MAX_CUE_CHARS: usize = 84;
+const MAX_CUE_MS: f64 = 7000.0;
+/// How long a segment without timing (most translations) stays on screen.
+const MIN_CUE_MS: f64 = 1500.0;
+
+/// Subtitle file written when the app exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Srt => "srt",
+            ExportFormat::Vtt => "vtt",
+        }
+    }
+}
+
+/// Layout of the live transcript file (`save_transcription`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    /// Plain paragraphs, overwritten on startup.
+    #[default]
+    Text,
+    /// One `{t_start_ms, t_end_ms, speaker, text}` JSON object per final segment, appended.
+    Jsonl,
+}
+
+struct Cue {
+    start_ms: f64,
+    end_ms: f64,
+    speaker: Option<String>,
+    text: String,
+}
+
+/// Collects finalized segments into timed cues and writes them as SRT or WebVTT.
+pub(crate) struct SubtitleExporter {
+    format: ExportFormat,
+    path: PathBuf,
+    cues: Vec<Cue>,
+    current: Option<Cue>,
+    /// Cues never go back in time, even if Soniox restarts its clock after a reconnect.
+    last_end_ms: f64,
+}
+
+impl SubtitleExporter {
+    pub(crate) fn new(format: ExportFormat, path: PathBuf) -> Self {
+        Self { format, path, cues: Vec::new(), current: None, last_end_ms: 0.0 }
+    }
+
+    /// Adds a final segment, timed from the start of the first session. Segments without timing
+    /// continue from the previous one and last `MIN_CUE_MS`.
+    pub(crate) fn record(&mut self, speaker: Option<&str>, text: &str, start_ms: Option<f64>, end_ms: Option<f64>) {
+        if text.trim().is_empty() {
+            return;
+        }
+        let start = start_ms.unwrap_or(self.last_end_ms).max(self.last_end_ms);
+        let end = end_ms.unwrap_or(start + MIN_CUE_MS).max(start);
+        self.last_end_ms = end;
+
+        if self.current.as_ref().is_some_and(|cue| cue.speaker.as_deref() != speaker) {
+            self.close_current();
+        }
+        let cue = self.current.get_or_insert_with(|| Cue {
+            start_ms: start,
+            end_ms: end,
+            speaker: speaker.map(str::to_string),
+            text: String::new(),
+        });
+        cue.text.push_str(text);
+        cue.end_ms = cue.end_ms.max(end);
+
+        let too_long = cue.text.trim().chars().count() >= MAX_CUE_CHARS || cue.end_ms - cue.start_ms >= MAX_CUE_MS;
+        if ends_sentence(&cue.text) || too_long {
+            self.close_current();
+        }
+    }
+
+    fn close_current(&mut self) {
+        if let Some(cue) = self.current.take() {
+            self.cues.push(cue);
+        }
+    }
+
+    /// Writes every cue collected so far to the export file.
+    pub(crate) fn finish(&mut self) {
+        self.close_current();
+        if self.cues.is_empty() {
+            return;
+        }
+
+        let mut out = String::new();
+        if self.format == ExportFormat::Vtt {
+            out.push_str("WEBVTT\n\n");
+        }
+        for (index, cue) in self.cues.iter().enumerate() {
+            if self.format == ExportFormat::Srt {
+                let _ = writeln!(out, "{}", index + 1);
+            }
+            let _ = writeln!(
+                out,
+                "{} --> {}",
+                format_timestamp(cue.start_ms, self.format),
+                format_timestamp(cue.end_ms, self.format)
+            );
+            match &cue.speaker {
+                Some(speaker) => { let _ = writeln!(out, "{}: {}\n", speaker, cue.text.trim()); }
+                None => { let _ = writeln!(out, "{}\n", cue.text.trim()); }
+            }
+        }
+
+        match std::fs::write(&self.path, out) {
+            Ok(()) => log::info!("Exported {} cues to {}", self.cues.len(), self.path.display()),
+            Err(e) => log::error!("Failed to write subtitle export '{}': {}", self.path.display(), e),
+        }
+    }
+}
+
+/// `HH:MM:SS,mmm` for SRT, `HH:MM:SS.mmm` for WebVTT.
+fn format_timestamp(ms: f64, format: ExportFormat) -> String {
+    let total = ms.max(0.0) as u64;
+    let separator = if format == ExportFormat::Srt { ',' } else { '.' };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        total / 3_600_000,
+        (total / 60_000) % 60,
+        (total / 1000) % 60,
+        separator,
+        total % 1000
+    )
+}
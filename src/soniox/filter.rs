@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+/// Masks configured words in displayed text, e.g. for streaming to a public audience.
+pub(crate) struct WordFilter {
+    /// Lowercased terms; matched against whole words only.
+    terms: HashSet<String>,
+}
+
+impl WordFilter {
+    pub(crate) fn new(terms: &[String]) -> Self {
+        let terms = terms
+            .iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        Self { terms }
+    }
+
+    /// Replaces every filtered word with asterisks of the same length (in chars).
+    /// Words are runs of alphanumeric chars, so "assistant" never matches "ass".
+    pub(crate) fn mask(&self, text: &str) -> String {
+        if self.terms.is_empty() {
+            return text.to_string();
+        }
+        self.mask_inner(text, None)
+    }
+
+    /// Like `mask`, also returning where each byte offset of `text` (up to and including its length)
+    /// lands in the masked text. Masked chars can be shorter than the originals, so byte ranges into
+    /// the original have to be moved with this.
+    pub(crate) fn mask_with_offsets(&self, text: &str) -> (String, Vec<usize>) {
+        let mut offsets = Vec::with_capacity(text.len() + 1);
+        let masked = self.mask_inner(text, Some(&mut offsets));
+        offsets.push(masked.len());
+        (masked, offsets)
+    }
+
+    fn mask_inner(&self, text: &str, mut offsets: Option<&mut Vec<usize>>) -> String {
+        let mut masked = String::with_capacity(text.len());
+        let mut word_start = 0;
+        for (i, c) in text.char_indices() {
+            if !c.is_alphanumeric() {
+                self.push_word(&mut masked, &text[word_start..i], offsets.as_deref_mut());
+                if let Some(offsets) = offsets.as_deref_mut() {
+                    offsets.extend(std::iter::repeat_n(masked.len(), c.len_utf8()));
+                }
+                masked.push(c);
+                word_start = i + c.len_utf8();
+            }
+        }
+        self.push_word(&mut masked, &text[word_start..], offsets);
+        masked
+    }
+
+    fn push_word(&self, out: &mut String, word: &str, offsets: Option<&mut Vec<usize>>) {
+        let filtered = self.terms.contains(&word.to_lowercase());
+        if let Some(offsets) = offsets {
+            // A masked char becomes a single '*' byte; a kept one keeps its bytes.
+            for (n, (i, c)) in word.char_indices().enumerate() {
+                let at = out.len() + if filtered { n } else { i };
+                offsets.extend(std::iter::repeat_n(at, c.len_utf8()));
+            }
+        }
+        if filtered {
+            out.extend(std::iter::repeat_n('*', word.chars().count()));
+        } else {
+            out.push_str(word);
+        }
+    }
+}
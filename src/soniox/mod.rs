@@ -1,7 +1,8 @@
+pub(crate) mod caption_server;
+pub mod clock;
+pub mod export;
+pub(crate) mod filter;
 pub(crate) mod state;
-// pub(crate) mod request; // Deprecated/Internal now, but kept if needed by other legacy. 
-// Actually I'll keep it for now but maybe I don't need to export it if stream uses modes.
-// pub(crate) mod request; 
 pub(crate) mod stream;
 pub(crate) mod modes;
 pub(crate) mod transcribe_mode;
@@ -1,11 +1,63 @@
 use crate::errors::SonioxWindowsErrors;
 use crate::types::settings::SettingsApp;
 use crate::types::soniox::SonioxTranscriptionRequest;
-use crate::soniox::state::TranscriptionState;
-use crate::types::soniox::SonioxTranscriptionResponse;
+use crate::soniox::state::{FinalSegment, TranscriptionState};
+use crate::types::soniox::{SonioxTranscriptionResponse, SonioxTranscriptionToken};
+
+/// What process_event does with one token, as decided by the mode.
+#[derive(Debug, Clone, Copy)]
+pub struct TokenUse {
+    /// Final tokens are committed and interim tokens join the interim line.
+    pub(crate) display: bool,
+    /// Final tokens advance `last_final_ms`, the dedup clock.
+    pub(crate) timing: bool,
+}
 
 pub trait SonioxMode {
     fn create_request<'a>(&self, settings: &'a SettingsApp, audio_format: (u32, u16)) -> Result<SonioxTranscriptionRequest<'a>, SonioxWindowsErrors>;
-    fn handle_incoming(&self, state: &mut TranscriptionState, response: SonioxTranscriptionResponse);
-    fn process_event(&self, state: &mut TranscriptionState, response: SonioxTranscriptionResponse);
+
+    /// The mode's token filter; everything else in process_event is shared.
+    fn token_use(&self, state: &mut TranscriptionState, token: &SonioxTranscriptionToken) -> TokenUse;
+
+    /// Whether a final token has to start a new segment instead of extending `segment`.
+    /// Speaker changes are already handled when speaker blocks are on.
+    fn splits_segment(&self, _segment: &FinalSegment, _token: &SonioxTranscriptionToken) -> bool {
+        false
+    }
+
+    /// Called for each final segment right before it is committed.
+    fn before_commit(&self, _state: &mut TranscriptionState, _segment: &mut FinalSegment) {}
+
+    /// Queues a response for process_event. A purely interim response replaces a queued purely
+    /// interim one from the same speaker, since it supersedes it.
+    fn handle_incoming(&self, state: &mut TranscriptionState, response: SonioxTranscriptionResponse) {
+        let is_purely_interim = !response.tokens.iter().any(|t| t.is_final);
+        let is_finished = response.finished == Some(true);
+
+        if is_purely_interim && !is_finished {
+            if let Some((_, last_response)) = state.event_queue.back_mut() {
+                let last_is_purely_interim = !last_response.tokens.iter().any(|t| t.is_final);
+                if last_is_purely_interim {
+                    let new_speaker = response.tokens.first().map(|t| &t.speaker);
+                    let last_speaker = last_response.tokens.first().map(|t| &t.speaker);
+                    if new_speaker == last_speaker {
+                        *last_response = response;
+                        return;
+                    }
+                }
+            }
+        }
+        let received = state.now();
+        state.event_queue.push_back((received, response));
+    }
+
+    fn process_event(&self, state: &mut TranscriptionState, response: SonioxTranscriptionResponse) {
+        state.process_response(self, response);
+    }
+
+    /// Called after a response with `finished: true` has been processed.
+    fn finalize(&self, state: &mut TranscriptionState) {
+        state.log_debug("FINISHED: Flushing interim".to_string());
+        state.flush_interim();
+    }
 }
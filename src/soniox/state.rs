@@ -1,49 +1,204 @@
-use crate::types::audio::AudioSubtitle;
-use crate::types::soniox::SonioxTranscriptionResponse;
+use crate::soniox::clock::{Clock, SystemClock};
+use crate::soniox::export::{ExportFormat, SubtitleExporter, TranscriptFormat};
+use crate::soniox::filter::WordFilter;
+use crate::soniox::modes::SonioxMode;
+use crate::types::audio::{AudioSubtitle, FreezeMode, RevealMode, TimestampMode, WAITING_TEXT};
+use crate::types::languages::LanguageHint;
+use crate::types::soniox::{SonioxTranscriptionResponse, SonioxTranscriptionToken};
 use std::collections::VecDeque;
+use std::ops::Range;
 use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Marker tokens Soniox emits for endpoints (`<end>`) and manual finalization (`<fin>`); never text.
+const CONTROL_MARKERS: [&str; 2] = ["<end>", "<fin>"];
+
+/// Final segments remembered to recognise ones a new Soniox session replays.
+const RECENT_FINALS: usize = 8;
+/// After a session restart, segments matching a recent final are dropped for this long.
+const REPLAY_WINDOW: Duration = Duration::from_secs(10);
+
+/// Responses held back by `min_line_display` are applied anyway once they are this many
+/// multiples of it old, so continuous speech can't build an ever-growing backlog.
+const MAX_LINE_BACKLOG: u32 = 3;
+
+/// How long captions take to fade out before `clear_after_silence` wipes them.
+const SILENCE_FADE: Duration = Duration::from_millis(500);
+
+/// One run of authoritative final text, as grouped by a mode's process_event.
+#[derive(Debug, Clone)]
+pub struct FinalSegment {
+    pub(crate) speaker: Option<String>,
+    pub(crate) language: Option<LanguageHint>,
+    /// Language the first token was spoken in (translation direction); None outside translation.
+    pub(crate) source_language: Option<LanguageHint>,
+    pub(crate) text: String,
+    /// Original speech behind a translated segment (`translate_display = "both"` or `"side_by_side"`).
+    pub(crate) source_text: Option<String>,
+    /// Audio span covered by the tokens, when Soniox reports timing (translations often don't).
+    pub(crate) start_ms: Option<f64>,
+    pub(crate) end_ms: Option<f64>,
+}
+
+impl FinalSegment {
+    pub(crate) fn from_token(token: &SonioxTranscriptionToken) -> Self {
+        Self {
+            speaker: token.speaker.clone(),
+            language: token.language,
+            source_language: token.source_language,
+            text: token.text.clone(),
+            source_text: None,
+            start_ms: token.start_ms,
+            end_ms: token.end_ms,
+        }
+    }
+
+    pub(crate) fn push_token(&mut self, token: &SonioxTranscriptionToken) {
+        self.speaker = token.speaker.clone();
+        self.language = token.language.or(self.language);
+        self.text.push_str(&token.text);
+        self.start_ms = self.start_ms.or(token.start_ms);
+        self.end_ms = token.end_ms.or(self.end_ms);
+    }
+}
 
 pub struct TranscriptionState {
     pub finishes_lines: VecDeque<AudioSubtitle>,
     pub interim_line: AudioSubtitle,
+    /// The interim line as drawn while a word filter is set: masked, with its typewriter progress and
+    /// dimmed ranges moved onto the masked text. Refreshed whenever the interim line changes.
+    pub(crate) interim_view: AudioSubtitle,
     pub(crate) max_lines: usize,
+    /// Measured in chars, not bytes, so accented and CJK text get the same room as ASCII.
     pub(crate) max_chars_in_block: usize,
     pub(crate) frozen_interim_history: String,
     pub(crate) frozen_blocks_count: usize,
     pub debug_log: VecDeque<String>,
     pub(crate) event_queue: VecDeque<(Instant, SonioxTranscriptionResponse)>,
+    /// Every timestamp and elapsed check goes through this, never Instant::now().
+    pub(crate) clock: Box<dyn Clock>,
 
     pub(crate) last_final_ms: f64,
+    /// Highest `total_audio_proc_ms` seen; it drops when a reconnect starts a new session.
+    pub(crate) last_audio_proc_ms: f64,
+    /// Audio processed by earlier Soniox sessions, so relative timestamps keep counting after a reconnect.
+    pub(crate) session_offset_ms: f64,
+    /// Label new finalized blocks with a timestamp of this kind.
+    pub(crate) timestamps: Option<TimestampMode>,
+    /// Audio time of the final segment push_final is committing; freezing interim text leaves it unset.
+    pub(crate) block_start_ms: Option<f64>,
+    /// When the current Soniox session replaced an earlier one.
+    pub(crate) session_restarted: Option<Instant>,
+    /// Trimmed text of the last committed final segments, newest last.
+    pub(crate) recent_finals: VecDeque<String>,
+    /// Makes the next push_final start a new block instead of merging into the last one.
+    pub(crate) force_new_block: bool,
+    /// Source language of the last committed final segment (two-way translation).
+    pub(crate) last_final_direction: Option<LanguageHint>,
     pub(crate) show_interim: bool,
+    /// Start a new block whenever the speaker changes instead of merging everyone into one run.
+    pub(crate) speaker_blocks: bool,
+    /// Masks configured words on screen, in finalized blocks and the interim line. The transcript log keeps the original.
+    pub(crate) word_filter: Option<WordFilter>,
+    /// Interim tokens below this confidence are drawn dimmed.
+    pub(crate) min_confidence: f64,
+    /// Final tokens below this confidence are dropped.
+    pub(crate) drop_confidence: f64,
     pub(crate) stability_timeout: Duration,
+    pub(crate) freeze_mode: FreezeMode,
+    /// Chunk process_event last wanted to freeze, and since when; it is frozen once unchanged for stability_timeout.
+    pub(crate) freeze_candidate: Option<(String, Instant)>,
+    /// How long responses wait in event_queue before they are shown; superseded interims are dropped meanwhile.
+    pub(crate) smart_delay: Duration,
+    /// Minimum time a new block stays the newest before a response with more final text is applied.
+    pub(crate) min_line_display: Duration,
+    /// When push_final last started a block.
+    pub(crate) last_block_at: Instant,
+    /// Typewriter delay per revealed char; zero shows text instantly.
+    pub(crate) animation_speed: Duration,
+    pub(crate) reveal_mode: RevealMode,
     pub(crate) last_interim_update: Instant,
+    /// Captions are cleared after this long without tokens; zero keeps them.
+    pub(crate) clear_after_silence: Duration,
+    /// When the last response with tokens was applied.
+    pub(crate) last_activity: Instant,
 
     // File Logging
     pub(crate) transcript_writer: Option<std::io::BufWriter<std::fs::File>>,
+    pub(crate) transcript_format: TranscriptFormat,
+    pub(crate) exporter: Option<SubtitleExporter>,
+    /// Receive every committed final segment, word filter applied (headless output, caption server).
+    pub(crate) final_sinks: Vec<UnboundedSender<FinalSegment>>,
+    /// Span of original tokens finalized but not yet translated; timing for translations that lack it.
+    pub(crate) pending_source_span: Option<(f64, f64)>,
+    /// Original final text not yet paired with its translation.
+    pub(crate) pending_source_text: String,
 }
 
 impl TranscriptionState {
     pub fn new(max_lines: usize, max_chars_in_block: usize) -> Self {
+        Self::with_clock(max_lines, max_chars_in_block, Box::new(SystemClock))
+    }
+
+    /// Like `new`, reading time from `clock` (a ManualClock makes the timers deterministic).
+    pub fn with_clock(max_lines: usize, max_chars_in_block: usize, clock: Box<dyn Clock>) -> Self {
         assert!(max_lines > 0);
+        let now = clock.now();
 
         Self {
             finishes_lines: VecDeque::with_capacity(max_lines),
             interim_line: AudioSubtitle::default(),
+            interim_view: AudioSubtitle::default(),
             max_lines,
             max_chars_in_block,
             frozen_interim_history: String::new(),
             frozen_blocks_count: 0,
             debug_log: VecDeque::with_capacity(20),
             event_queue: VecDeque::new(),
+            clock,
 
             last_final_ms: 0.0,
+            last_audio_proc_ms: 0.0,
+            session_offset_ms: 0.0,
+            timestamps: None,
+            block_start_ms: None,
+            session_restarted: None,
+            recent_finals: VecDeque::with_capacity(RECENT_FINALS),
+            force_new_block: false,
+            last_final_direction: None,
             show_interim: true,
+            speaker_blocks: false,
+            word_filter: None,
+            min_confidence: 0.0,
+            drop_confidence: 0.0,
             stability_timeout: Duration::from_millis(0),
-            last_interim_update: Instant::now(),
+            freeze_mode: FreezeMode::Punctuation,
+            freeze_candidate: None,
+            smart_delay: Duration::ZERO,
+            min_line_display: Duration::ZERO,
+            last_block_at: now,
+            animation_speed: Duration::from_millis(20),
+            reveal_mode: RevealMode::Typewriter,
+            last_interim_update: now,
+            clear_after_silence: Duration::ZERO,
+            last_activity: now,
             transcript_writer: None,
+            transcript_format: TranscriptFormat::Text,
+            exporter: None,
+            final_sinks: Vec::new(),
+            pending_source_span: None,
+            pending_source_text: String::new(),
         }
     }
 
+    pub(crate) fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.clock.now().saturating_duration_since(earlier)
+    }
+
     pub fn log_debug(&mut self, msg: String) {
         if self.debug_log.len() >= 20 {
             self.debug_log.pop_front();
@@ -55,10 +210,13 @@ impl TranscriptionState {
         self.debug_log.iter().cloned().collect()
     }
 
+    /// Lines to draw. Without `show_interim` the interim line is left out entirely, which also
+    /// hides the waiting placeholder; draw_text_with_shadow then sizes itself from the final blocks.
     pub fn iter(&self) -> impl Iterator<Item = &AudioSubtitle> {
         // Return in chronological order: [oldest_final, ..., newest_final, interim]
+        let interim = if self.word_filter.is_some() { &self.interim_view } else { &self.interim_line };
         let interim_iter = if self.show_interim {
-            Some(&self.interim_line).into_iter()
+            Some(interim).into_iter()
         } else {
             None.into_iter()
         };
@@ -66,6 +224,15 @@ impl TranscriptionState {
             .chain(interim_iter)
     }
 
+    /// The lines the overlay shows, oldest first, without the waiting placeholder.
+    pub fn visible_text(&self) -> String {
+        self.iter()
+            .map(|line| line.text.trim())
+            .filter(|text| !text.is_empty() && *text != WAITING_TEXT)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     pub fn set_max_chars(&mut self, max_chars: usize) {
         self.max_chars_in_block = max_chars;
     }
@@ -81,17 +248,285 @@ impl TranscriptionState {
         self.stability_timeout = Duration::from_millis(timeout_ms);
     }
 
+    /// Holds a freeze at `split` back until the interim text before it has been proposed unchanged
+    /// for `stability_timeout`, so churning interim text isn't frozen and then backtracked.
+    pub(crate) fn hold_until_stable(&mut self, interim: &str, split: Option<usize>) -> Option<usize> {
+        let Some(idx) = split else {
+            self.freeze_candidate = None;
+            return None;
+        };
+        if self.stability_timeout.is_zero() {
+            return Some(idx);
+        }
+        let chunk = &interim[..idx];
+        let now = self.now();
+        match &self.freeze_candidate {
+            Some((candidate, since)) if candidate == chunk => {
+                if now.saturating_duration_since(*since) < self.stability_timeout {
+                    return None;
+                }
+                self.freeze_candidate = None;
+                Some(idx)
+            }
+            _ => {
+                self.freeze_candidate = Some((chunk.to_string(), now));
+                None
+            }
+        }
+    }
+
+    pub fn set_freeze_mode(&mut self, freeze_mode: FreezeMode) {
+        self.freeze_mode = freeze_mode;
+    }
+
+    pub fn set_animation_speed(&mut self, ms_per_char: u64, reveal_mode: RevealMode) {
+        self.animation_speed = Duration::from_millis(ms_per_char);
+        self.reveal_mode = reveal_mode;
+    }
+
+    pub fn set_smart_delay(&mut self, delay_ms: u64) {
+        self.smart_delay = Duration::from_millis(delay_ms);
+    }
+
+    /// Only blocks created afterwards are labelled (or not).
+    pub fn set_timestamps(&mut self, timestamps: Option<TimestampMode>) {
+        self.timestamps = timestamps;
+    }
+
+    pub fn set_min_line_display(&mut self, ms: u64) {
+        self.min_line_display = Duration::from_millis(ms);
+    }
+
+    pub fn set_clear_after_silence(&mut self, ms: u64) {
+        self.clear_after_silence = Duration::from_millis(ms);
+    }
+
+    /// Opacity for the captions while they fade out ahead of a silence clear; 1.0 otherwise.
+    pub fn silence_opacity(&self) -> f32 {
+        if self.clear_after_silence.is_zero() {
+            return 1.0;
+        }
+        let fade = SILENCE_FADE.min(self.clear_after_silence);
+        let remaining = self.clear_after_silence.saturating_sub(self.elapsed_since(self.last_activity));
+        (remaining.as_secs_f32() / fade.as_secs_f32()).min(1.0)
+    }
+
+    /// Whether anything besides the waiting placeholder is on screen.
+    fn has_captions(&self) -> bool {
+        !self.finishes_lines.is_empty() || !(self.interim_line.text.is_empty() || self.interim_line.text == WAITING_TEXT)
+    }
+
+    pub fn set_speaker_blocks(&mut self, enabled: bool) {
+        self.speaker_blocks = enabled;
+    }
+
+    pub fn set_word_filter(&mut self, terms: &[String]) {
+        self.word_filter = (!terms.is_empty()).then(|| WordFilter::new(terms));
+        self.refresh_interim_view();
+    }
+
+    /// Rebuilds `interim_view` from the interim line. Masking the whole text and cutting the masked
+    /// prefix keeps a filtered word hidden while the typewriter is still halfway through it.
+    fn refresh_interim_view(&mut self) {
+        let Some(filter) = &self.word_filter else {
+            return;
+        };
+        let line = &self.interim_line;
+        let (text, offsets) = filter.mask_with_offsets(&line.text);
+        let at = |i: usize| offsets[i.min(line.text.len())];
+        let mut view = line.clone();
+        view.displayed_text = text[..at(line.displayed_text.len())].to_string();
+        view.low_confidence = line.low_confidence.iter().map(|r| at(r.start)..at(r.end)).collect();
+        view.text = text;
+        self.interim_view = view;
+    }
+
+    pub fn set_confidence_thresholds(&mut self, min_confidence: f64, drop_confidence: f64) {
+        self.min_confidence = min_confidence;
+        self.drop_confidence = drop_confidence;
+    }
+
     pub fn get_active_char_count(&self) -> usize {
-        self.finishes_lines.front().map(|l| l.text.len()).unwrap_or(0)
+        self.finishes_lines.front().map(|l| l.text.chars().count()).unwrap_or(0)
     }
 
     pub fn get_frozen_block_count(&self) -> usize {
         self.finishes_lines.len()
     }
 
+    /// Shared body of `SonioxMode::process_event`: splits a response into final segments and the
+    /// interim line, commits the finals and freezes interim text where the freeze mode allows.
+    /// The mode's hooks only decide which tokens are shown and where final segments break.
+    pub(crate) fn process_response<M: SonioxMode + ?Sized>(&mut self, mode: &M, response: SonioxTranscriptionResponse) {
+        let mut full_interim_text = String::new();
+        let mut interim_speaker = Option::<String>::None;
+        let mut interim_language = Option::<LanguageHint>::None;
+        let mut low_confidence = Vec::new();
+        // Runs of final text; split on speaker changes when speaker blocks are on.
+        let mut final_segments: Vec<FinalSegment> = Vec::new();
+
+        // A reconnect starts a new session whose clock begins at zero again, so the end_ms
+        // dedup would drop everything it finalizes. Start over and watch for replayed text instead.
+        if response.total_audio_proc_ms > 0.0 {
+            if response.total_audio_proc_ms < self.last_audio_proc_ms {
+                self.log_debug("SESSION: Soniox clock restarted".to_string());
+                self.last_final_ms = 0.0;
+                self.session_offset_ms += self.last_audio_proc_ms;
+                self.session_restarted = Some(self.now());
+            }
+            self.last_audio_proc_ms = response.total_audio_proc_ms;
+        }
+
+        let mut max_ms = self.last_final_ms;
+
+        for token in response.tokens {
+            // Sanitizer: control markers never reach the screen or the transcript, whatever the mode.
+            if CONTROL_MARKERS.iter().any(|marker| token.text.contains(marker)) {
+                continue;
+            }
+            let usage = mode.token_use(self, &token);
+            if token.is_final {
+                // Timing update: track the furthest point finalized by the AI
+                if usage.timing {
+                    if let Some(end_ms) = token.end_ms {
+                        max_ms = max_ms.max(end_ms);
+                    }
+                }
+                if !usage.display {
+                    continue;
+                }
+                // Deduplicate based on end_ms if available.
+                // Note: Translation tokens often lack end_ms, but they are typically
+                // sent once per finalized segment.
+                if token.end_ms.is_some_and(|end_ms| end_ms <= self.last_final_ms) {
+                    continue;
+                }
+                if token.confidence < self.drop_confidence {
+                    continue;
+                }
+                match final_segments.last_mut() {
+                    Some(segment)
+                        if (!self.speaker_blocks || segment.speaker == token.speaker)
+                            && !mode.splits_segment(segment, &token) =>
+                    {
+                        segment.push_token(&token);
+                    }
+                    _ => final_segments.push(FinalSegment::from_token(&token)),
+                }
+            } else if usage.display {
+                // INTERIM processing.
+                if interim_speaker != token.speaker {
+                    interim_speaker = token.speaker.clone();
+                }
+                interim_language = token.language.or(interim_language);
+                let start = full_interim_text.len();
+                full_interim_text.push_str(&token.text);
+                if token.confidence < self.min_confidence {
+                    low_confidence.push(start..full_interim_text.len());
+                }
+            }
+        }
+
+        self.last_final_ms = max_ms;
+
+        for mut segment in final_segments {
+            mode.before_commit(self, &mut segment);
+            if self.is_replayed(&segment) {
+                self.log_debug(format!("DEDUP: Replayed after reconnect '{}'", segment.text.trim()));
+                continue;
+            }
+            self.commit_final(segment);
+        }
+        // CRITICAL: Don't call update_interim("") here if we are about to call it with text below.
+        // That's what causes the "spin". We'll update it at the very end of this function.
+
+        let mut next_interim_text = String::new();
+
+        if !full_interim_text.is_empty() {
+             if !full_interim_text.starts_with(&self.frozen_interim_history) {
+                 self.log_debug("Interim drift! Resetting ghosts.".to_string());
+                 for _ in 0..self.frozen_blocks_count {
+                     self.finishes_lines.pop_front();
+                 }
+                 self.frozen_blocks_count = 0;
+                 self.frozen_interim_history.clear();
+             }
+
+             let effective_interim = full_interim_text[self.frozen_interim_history.len()..].to_string();
+             // Dynamic limit for splitting is higher than the wrapping limit to allow natural flow.
+             let split_limit = self.max_chars_in_block.max(100);
+
+             // The stability strategy leaves word-boundary freezing to update_animation; overflow still splits.
+             let sentence_split = match self.freeze_mode {
+                 FreezeMode::Punctuation => find_sentence_split(&effective_interim, split_limit),
+                 FreezeMode::Stability => None,
+             };
+             let (split, reason) = if sentence_split.is_some() {
+                 (sentence_split, "Sentence")
+             } else if effective_interim.chars().count() > split_limit + 50 { // Even more slack
+                 (find_overflow_split(&effective_interim, split_limit), "Size")
+             } else {
+                 (None, "")
+             };
+
+             if let Some(idx) = self.hold_until_stable(&effective_interim, split) {
+                let (frozen_chunk, remainder) = effective_interim.split_at(idx);
+                let frozen_chunk_str = frozen_chunk.to_string();
+                self.log_debug(format!("FREEZE ({}): '{}'", reason, frozen_chunk_str.trim()));
+                self.frozen_interim_history.push_str(&frozen_chunk_str);
+                let added = self.push_final(interim_speaker.clone(), interim_language, frozen_chunk_str, false);
+                self.frozen_blocks_count += added;
+                next_interim_text = remainder.to_string();
+             } else {
+                next_interim_text = effective_interim;
+             }
+        }
+
+        // Final update to interim line
+        if self.interim_line.text != next_interim_text {
+            self.last_interim_update = self.now();
+        }
+        let consumed = full_interim_text.len() - next_interim_text.len();
+        self.update_interim(interim_speaker, interim_language, next_interim_text);
+        self.interim_line.low_confidence = shift_ranges(&low_confidence, consumed);
+        self.refresh_interim_view();
+    }
+
+    /// Applies queued responses that have waited at least `smart_delay`. Responses carrying final
+    /// text also wait until the newest block has been shown for `min_line_display`.
     pub fn process_pending_events(&mut self, mode: &dyn crate::soniox::modes::SonioxMode) {
+        while let Some((received, response)) = self.event_queue.front() {
+            let waited = self.elapsed_since(*received);
+            if waited < self.smart_delay {
+                break;
+            }
+            let has_finals = response.tokens.iter().any(|t| t.is_final);
+            if has_finals
+                && self.elapsed_since(self.last_block_at) < self.min_line_display
+                && waited < self.min_line_display * MAX_LINE_BACKLOG
+            {
+                break;
+            }
+            let (_, response) = self.event_queue.pop_front().expect("checked");
+            self.apply_event(mode, response);
+        }
+    }
+
+    /// Applies every queued response now, regardless of `smart_delay`.
+    pub fn flush_pending_events(&mut self, mode: &dyn crate::soniox::modes::SonioxMode) {
         while let Some((_, response)) = self.event_queue.pop_front() {
-            mode.process_event(self, response);
+            self.apply_event(mode, response);
+        }
+    }
+
+    fn apply_event(&mut self, mode: &dyn crate::soniox::modes::SonioxMode, response: SonioxTranscriptionResponse) {
+        let finished = response.finished == Some(true);
+        if !response.tokens.is_empty() {
+            self.last_activity = self.now();
+        }
+        mode.process_event(self, response);
+        if finished {
+            mode.finalize(self);
         }
     }
 
@@ -100,15 +535,24 @@ impl TranscriptionState {
     pub fn update_animation(&mut self, mode: &dyn crate::soniox::modes::SonioxMode) -> bool {
         self.process_pending_events(mode);
 
+        // Held-back responses count as activity, so a min_line_display backlog isn't cleared away.
+        if !self.clear_after_silence.is_zero()
+            && self.event_queue.is_empty()
+            && self.elapsed_since(self.last_activity) >= self.clear_after_silence
+            && self.has_captions()
+        {
+            self.log_debug("SILENCE: clearing captions".to_string());
+            self.clear();
+            return true;
+        }
+
         // Check for stability timeout
-        // Check for stability timeout
-        if !self.interim_line.text.is_empty() && self.last_interim_update.elapsed() >= self.stability_timeout {
+        if !self.interim_line.text.is_empty() && self.elapsed_since(self.last_interim_update) >= self.stability_timeout {
             let text_clone = self.interim_line.text.clone();
             
-            // Smart Freeze: Only freeze up to the last word boundary (whitespace)
+            // Smart Freeze: Only freeze up to the last word boundary (whitespace, or a CJK break)
             // This prevents "Iamthe" merging by ensuring we only commit complete words.
-            if let Some(last_space_idx) = text_clone.rfind(char::is_whitespace) {
-                let split_idx = last_space_idx + 1; // Include the space
+            if let Some(split_idx) = find_last_freeze_point(&text_clone) {
                 let (frozen_part, remainder) = text_clone.split_at(split_idx);
                 let frozen_string = frozen_part.to_string();
                 let remainder_string = remainder.to_string();
@@ -116,16 +560,18 @@ impl TranscriptionState {
                 self.log_debug(format!("STABILITY: Freezing '{}'", frozen_string.trim()));
                 
                 let speaker = self.interim_line.speaker.clone();
+                let language = self.interim_line.language;
                 self.frozen_interim_history.push_str(&frozen_string);
-                let added = self.push_final(speaker, frozen_string, false);
+                let added = self.push_final(speaker, language, frozen_string, false);
                 self.frozen_blocks_count += added;
                 
                 // Keep the remainder as the new interim line
                 self.interim_line.text = remainder_string;
+                self.interim_line.low_confidence = shift_ranges(&self.interim_line.low_confidence, split_idx);
                 // Reset displayed text to restart typing for the remainder
                 self.interim_line.displayed_text.clear();
                 // Reset timer so the remainder has a fair chance to complete
-                self.last_interim_update = Instant::now();
+                self.last_interim_update = self.now();
             }
         }
 
@@ -149,10 +595,10 @@ impl TranscriptionState {
                 break;
             }
             
-            // If we have a backlog, speed up the typewriter (20ms -> 10ms or less)
+            // If we have a backlog, speed up the typewriter (up to 4 chars per tick)
             let speed_boost = if waiting_count > 1 { (waiting_count as usize).min(4) } else { 1 };
             for i in 0..speed_boost {
-                if line.update_animation(i > 0) {
+                if line.update_animation(i > 0, self.animation_speed, self.reveal_mode) {
                     request_repaint = true;
                 }
             }
@@ -164,10 +610,16 @@ impl TranscriptionState {
 
         // Only animate interim if all final lines are finished
         if !animation_blocked {
-            if self.interim_line.update_animation(false) {
+            // Instant mode only applies to final text; the interim line keeps streaming.
+            let interim_reveal = match self.reveal_mode {
+                RevealMode::Instant => RevealMode::Typewriter,
+                other => other,
+            };
+            if self.interim_line.update_animation(false, self.animation_speed, interim_reveal) {
                 request_repaint = true;
             }
         }
+        self.refresh_interim_view();
 
         request_repaint
     }
@@ -187,7 +639,7 @@ impl TranscriptionState {
              // 2. Handle the very end of the block (e.g. "Sentence three.")
              // If it ends with punctuation and NOT a newline (from step 1), append break.
              let trimmed = content.trim_end();
-             let ends_with_punct = trimmed.ends_with('.') || trimmed.ends_with('!') || trimmed.ends_with('?');
+             let ends_with_punct = ends_sentence(trimmed);
              
              if ends_with_punct {
                 // If step 1 already added newlines (because of trailing space), don't double up.
@@ -217,8 +669,115 @@ impl TranscriptionState {
         }
     }
 
-    pub(crate) fn push_final(&mut self, speaker: Option<String>, mut text: String, instant: bool) -> usize {
+    /// Appends one JSON line per final segment and flushes it, so a crash loses nothing already shown.
+    /// Times are from the start of the first session, so they keep increasing across reconnects.
+    fn log_final_record(&mut self, speaker: Option<&str>, text: &str, start_ms: Option<f64>, end_ms: Option<f64>) {
+        if let Some(writer) = &mut self.transcript_writer {
+            use std::io::Write;
+
+            let record = serde_json::json!({
+                "t_start_ms": start_ms,
+                "t_end_ms": end_ms,
+                "speaker": speaker,
+                "text": text.trim(),
+            });
+            if let Err(e) = writeln!(writer, "{}", record) {
+                log::error!("Failed to write to transcript log: {}", e);
+            }
+            let _ = writer.flush();
+        }
+    }
+
+    /// Commits an authoritative final segment, reconciling it with the interim text that was
+    /// already frozen on screen (ghost blocks): pushes only the new suffix, skips text that is
+    /// already covered, or backtracks the ghosts if the final text disagrees with them.
+    pub(crate) fn commit_final(&mut self, segment: FinalSegment) {
+        self.notify_final_sinks(&segment);
+        if self.recent_finals.len() >= RECENT_FINALS {
+            self.recent_finals.pop_front();
+        }
+        self.recent_finals.push_back(segment.text.trim().to_string());
+        let FinalSegment { speaker, language, text: final_text_segment, source_text, start_ms, end_ms, .. } = segment;
+        // Soniox times each session from zero; the transcript and export span all of them.
+        let offset = self.session_offset_ms;
+        let (abs_start_ms, abs_end_ms) = (start_ms.map(|ms| offset + ms), end_ms.map(|ms| offset + ms));
+        // Log the authoritative final text (decoupled from screen state/freezing)
+        match self.transcript_format {
+            TranscriptFormat::Text => self.log_final_text(&final_text_segment),
+            TranscriptFormat::Jsonl => self.log_final_record(speaker.as_deref(), &final_text_segment, abs_start_ms, abs_end_ms),
+        }
+        if let Some(exporter) = &mut self.exporter {
+            exporter.record(speaker.as_deref(), &final_text_segment, abs_start_ms, abs_end_ms);
+        }
+
+        self.block_start_ms = start_ms;
+        if final_text_segment.starts_with(&self.frozen_interim_history) {
+             let text_to_push = final_text_segment[self.frozen_interim_history.len()..].to_string();
+             self.log_debug(format!("FINAL: Pushing suffix '{}'", text_to_push.trim()));
+             self.push_final(speaker, language, text_to_push, false);
+             self.frozen_blocks_count = 0;
+             self.frozen_interim_history.clear();
+        } else if self.frozen_interim_history.starts_with(&final_text_segment) {
+             self.log_debug(format!("FINAL: Already covered by history '{}'", final_text_segment.trim()));
+             self.frozen_interim_history.drain(..final_text_segment.len());
+        } else {
+            self.log_debug(format!("BACKTRACK: {} ghosts because of '{}'", self.frozen_blocks_count, final_text_segment.trim()));
+            for _ in 0..self.frozen_blocks_count {
+                self.finishes_lines.pop_front();
+            }
+            self.push_final(speaker, language, final_text_segment, false);
+            self.frozen_blocks_count = 0;
+            self.frozen_interim_history.clear();
+        }
+        self.block_start_ms = None;
+        // The source goes with the block the translation ended up in, ghost or new.
+        if let (Some(source), Some(block)) = (source_text, self.finishes_lines.front_mut()) {
+            block.source_text.get_or_insert_with(String::new).push_str(&source);
+        }
+    }
+
+    /// Whether a new session is repeating a segment the previous one already finalized. end_ms can't
+    /// tell (the clock restarted, and translations often have no timing), so this compares text.
+    fn is_replayed(&self, segment: &FinalSegment) -> bool {
+        self.session_restarted.is_some_and(|restarted| self.elapsed_since(restarted) < REPLAY_WINDOW)
+            && self.recent_finals.iter().any(|text| text == segment.text.trim())
+    }
+
+    pub(crate) fn add_final_sink(&mut self, sink: UnboundedSender<FinalSegment>) {
+        self.final_sinks.push(sink);
+    }
+
+    /// Hands the segment to every sink, dropping sinks whose receiver has gone away. Its times are
+    /// shifted onto the first session's clock, like the transcript's.
+    fn notify_final_sinks(&mut self, segment: &FinalSegment) {
+        if self.final_sinks.is_empty() {
+            return;
+        }
+        let mut shared = segment.clone();
+        if let Some(filter) = &self.word_filter {
+            shared.text = filter.mask(&shared.text);
+        }
+        let offset = self.session_offset_ms;
+        shared.start_ms = shared.start_ms.map(|ms| offset + ms);
+        shared.end_ms = shared.end_ms.map(|ms| offset + ms);
+        self.final_sinks.retain(|sink| sink.send(shared.clone()).is_ok());
+    }
+
+    /// Frozen interim text has no final timing yet; it starts about where the finalized audio ends.
+    fn format_timestamp(&self, mode: TimestampMode) -> String {
+        match mode {
+            TimestampMode::Relative => {
+                let start_ms = self.block_start_ms.unwrap_or(self.last_final_ms);
+                let secs = ((self.session_offset_ms + start_ms) / 1000.0) as u64;
+                format!("[{:02}:{:02}:{:02}]", secs / 3600, secs / 60 % 60, secs % 60)
+            }
+            TimestampMode::Clock => chrono::Local::now().format("[%H:%M:%S]").to_string(),
+        }
+    }
+
+    pub(crate) fn push_final(&mut self, speaker: Option<String>, language: Option<LanguageHint>, mut text: String, instant: bool) -> usize {
         if text.is_empty() { return 0; }
+        let timestamp = self.timestamps.map(|mode| self.format_timestamp(mode));
         let mut added = 0;
 
         loop {
@@ -235,12 +794,15 @@ impl TranscriptionState {
 
             // 2. Decide if we start a new block or merge
             let (should_start_new, _reason) = match self.finishes_lines.front() {
+                Some(_) if std::mem::take(&mut self.force_new_block) => (true, "Forced"),
+                Some(last) if self.speaker_blocks && last.speaker != speaker => (true, "Speaker change"),
+                Some(last) if language.is_some() && last.language.is_some() && last.language != language => (true, "Language change"),
                 Some(last) => {
                     let last_trimmed = last.text.trim_end();
-                    let ends_sentence = last_trimmed.ends_with(|c| c == '.' || c == '?' || c == '!');
+                    let ends_sentence = ends_sentence(last_trimmed);
                     
                     // Fallback to prevent infinite block growth if there's no punctuation
-                    let too_long = last.text.len() > 200; 
+                    let too_long = last.text.chars().count() > 200;
                     let is_mid_word = !last.text.ends_with(char::is_whitespace) && !chunk.starts_with(char::is_whitespace);
                     
                     if ends_sentence {
@@ -256,7 +818,11 @@ impl TranscriptionState {
 
             if should_start_new {
                 // self.log_debug(format!("BLOCK: New ({})", reason));
-                let mut sub = AudioSubtitle::new(speaker.clone(), chunk);
+                self.last_block_at = self.now();
+                let mut sub = AudioSubtitle::new(speaker.clone(), String::new());
+                append_text(&mut sub, &chunk, self.word_filter.as_ref());
+                sub.language = language;
+                sub.timestamp = timestamp.clone();
                 if instant { sub.displayed_text = sub.text.clone(); }
                 self.finishes_lines.push_front(sub);
                 added += 1;
@@ -266,16 +832,16 @@ impl TranscriptionState {
                 let last_ends_with_space = last.text.ends_with(char::is_whitespace);
                 let chunk_starts_with_space = chunk.starts_with(char::is_whitespace);
                 
-                if !last_ends_with_space && chunk_starts_with_space && chunk.trim_start().len() <= 2 {
+                if !last_ends_with_space && chunk_starts_with_space && chunk.trim_start().chars().count() <= 2 {
                     // Hungarian fragment fix (milli + ó)
-                    last.text.push_str(chunk.trim_start());
+                    append_text(last, chunk.trim_start(), self.word_filter.as_ref());
                 } else {
-                    last.text.push_str(&chunk);
+                    append_text(last, &chunk, self.word_filter.as_ref());
                 }
                 if instant { last.displayed_text = last.text.clone(); }
             }
 
-            if self.finishes_lines.len() >= self.max_lines {
+            while self.finishes_lines.len() > self.max_lines {
                 self.finishes_lines.pop_back();
             }
 
@@ -288,13 +854,68 @@ impl TranscriptionState {
         added
     }
 
-    pub(crate) fn update_interim(&mut self, speaker: Option<String>, text: String) {
+    /// Wipes every line from the screen, interim included. Logging and export are unaffected.
+    /// Call flush_pending_events first so already-received responses don't reappear afterwards.
+    pub fn clear(&mut self) {
+        self.finishes_lines.clear();
+        self.interim_line = AudioSubtitle::default();
+        self.frozen_interim_history.clear();
+        self.frozen_blocks_count = 0;
+        self.freeze_candidate = None;
+        self.force_new_block = true;
+        self.last_interim_update = self.now();
+        self.refresh_interim_view();
+        self.log_debug("CLEAR: buffer wiped".to_string());
+    }
+
+    /// Freezes the whole interim line into a block of its own right away (the commit hotkey), without
+    /// waiting for an endpoint or the stability timeout. Like other frozen text it is kept in
+    /// frozen_interim_history, so the same words aren't shown twice once Soniox finalizes them.
+    pub fn commit_interim(&mut self) {
+        let text = std::mem::take(&mut self.interim_line.text);
+        self.interim_line.displayed_text.clear();
+        self.interim_line.low_confidence.clear();
+        self.freeze_candidate = None;
+        self.last_interim_update = self.now();
+        self.refresh_interim_view();
+        if text.trim().is_empty() || text == WAITING_TEXT {
+            return;
+        }
+        self.log_debug(format!("COMMIT: '{}'", text.trim()));
+        let speaker = self.interim_line.speaker.clone();
+        let language = self.interim_line.language;
+        self.frozen_interim_history.push_str(&text);
+        self.force_new_block = true;
+        let added = self.push_final(speaker, language, text, true);
+        self.frozen_blocks_count += added;
+        // Whatever is said next starts a block of its own too.
+        self.force_new_block = true;
+    }
+
+    /// Promotes whatever is left on the interim line to a final block and forgets the
+    /// frozen history, so the next utterance starts clean.
+    pub(crate) fn flush_interim(&mut self) {
+        let text = std::mem::take(&mut self.interim_line.text);
+        self.interim_line.displayed_text.clear();
+        self.interim_line.low_confidence.clear();
+        self.refresh_interim_view();
+        if !text.trim().is_empty() && text != WAITING_TEXT {
+            let speaker = self.interim_line.speaker.clone();
+            let language = self.interim_line.language;
+            self.push_final(speaker, language, text, false);
+        }
+        self.frozen_interim_history.clear();
+        self.frozen_blocks_count = 0;
+    }
+
+    pub(crate) fn update_interim(&mut self, speaker: Option<String>, language: Option<LanguageHint>, text: String) {
         // If the text is the same, do nothing.
-        if self.interim_line.text == text && self.interim_line.speaker == speaker {
+        if self.interim_line.text == text && self.interim_line.speaker == speaker && self.interim_line.language == language {
             return;
         }
 
         self.interim_line.speaker = speaker;
+        self.interim_line.language = language;
         let old_text = std::mem::replace(&mut self.interim_line.text, text);
         
         // Anti-spin / Typewriter preservation:
@@ -319,13 +940,31 @@ impl TranscriptionState {
     }
     
     // Logging Logic
-    pub(crate) fn set_logging(&mut self, enabled: bool, path: &str) {
+    /// Collects timed cues for an SRT/VTT file next to `transcript_path`, written by `finish_export`.
+    pub(crate) fn set_export(&mut self, format: Option<ExportFormat>, transcript_path: &str) {
+        self.exporter = format.map(|format| {
+            let path = std::path::Path::new(transcript_path).with_extension(format.extension());
+            SubtitleExporter::new(format, path)
+        });
+    }
+
+    pub fn finish_export(&mut self) {
+        if let Some(exporter) = &mut self.exporter {
+            exporter.finish();
+        }
+    }
+
+    pub(crate) fn set_logging(&mut self, enabled: bool, path: &str, format: TranscriptFormat) {
+        self.transcript_format = format;
         if enabled {
-             let f = std::fs::OpenOptions::new()
-                .create(true)
-                .write(true)
-                .truncate(true)
-                .open(path);
+             // JSON lines accumulate across sessions; the plain-text transcript starts fresh.
+             let mut options = std::fs::OpenOptions::new();
+             options.create(true);
+             match format {
+                 TranscriptFormat::Text => options.write(true).truncate(true),
+                 TranscriptFormat::Jsonl => options.append(true),
+             };
+             let f = options.open(path);
              match f {
                  Ok(file) => {
                      self.transcript_writer = Some(std::io::BufWriter::new(file));
@@ -340,12 +979,363 @@ impl TranscriptionState {
     }
 }
 
+/// Re-bases byte ranges onto the text that starts `offset` bytes in, dropping ranges that end before it.
+pub(crate) fn shift_ranges(ranges: &[Range<usize>], offset: usize) -> Vec<Range<usize>> {
+    ranges
+        .iter()
+        .filter(|r| r.end > offset)
+        .map(|r| r.start.saturating_sub(offset)..r.end - offset)
+        .collect()
+}
+
+/// Appends `text` to a finalized block. With a filter, the block's joined unmasked text is masked
+/// again, so a word finalized in pieces ("fu" + "dge") is matched as a whole.
+fn append_text(block: &mut AudioSubtitle, text: &str, filter: Option<&WordFilter>) {
+    let Some(filter) = filter else {
+        block.text.push_str(text);
+        return;
+    };
+    let unmasked = block.unmasked_text.get_or_insert_with(|| block.text.clone());
+    unmasked.push_str(text);
+    block.text = filter.mask(unmasked);
+    // Masking keeps the char count, so the typewriter goes on from the same char.
+    let shown = block.displayed_text.chars().count();
+    block.displayed_text = block.text.chars().take(shown).collect();
+}
+
+/// Latin-style terminators: only end a sentence when followed by whitespace ("3.14" stays intact).
+fn is_spaced_terminator(c: char) -> bool {
+    matches!(c, '.' | '?' | '!')
+}
+
+/// Full-width CJK terminators. These scripts don't put a space after a sentence.
+fn is_cjk_terminator(c: char) -> bool {
+    matches!(c, '。' | '！' | '？' | '．' | '｡')
+}
+
+/// Ideographic commas: not a sentence end, but a safe place to break an overlong CJK run
+/// that has no whitespace to split on.
+fn is_cjk_clause_break(c: char) -> bool {
+    matches!(c, '、' | '，' | '､')
+}
+
+/// True if `text` (ignoring trailing whitespace) ends with a sentence terminator of any script.
+pub(crate) fn ends_sentence(text: &str) -> bool {
+    text.trim_end()
+        .chars()
+        .last()
+        .is_some_and(|c| is_spaced_terminator(c) || is_cjk_terminator(c))
+}
+
+/// Byte index just past the first sentence end within the first `limit` chars, always on a char boundary.
 pub(crate) fn find_sentence_split(text: &str, limit: usize) -> Option<usize> {
+    let mut chars = text.char_indices().enumerate().peekable();
+    while let Some((n, (i, c))) = chars.next() {
+        if n >= limit {
+            break;
+        }
+        let next = chars.peek().map(|&(_, (_, next))| next);
+        let is_end = if is_cjk_terminator(c) {
+            // Split right after the terminator, unless it is the last char (nothing to split off).
+            next.is_some()
+        } else {
+            is_spaced_terminator(c) && next.is_some_and(char::is_whitespace)
+        };
+        if is_end {
+            return Some(i + c.len_utf8());
+        }
+    }
+    None
+}
+
+/// Where to break an overlong run with no sentence end: the first whitespace at or after char `limit`,
+/// or just past an ideographic comma. Returns a byte index on a char boundary.
+pub(crate) fn find_overflow_split(text: &str, limit: usize) -> Option<usize> {
     text.char_indices()
-        .zip(text.chars().skip(1))
-        .filter(|((i, c), next_c)| {
-            *i < limit && (*c == '.' || *c == '?' || *c == '!') && next_c.is_whitespace()
+        .skip(limit)
+        .find_map(|(i, c)| {
+            if c.is_whitespace() {
+                Some(i)
+            } else if is_cjk_clause_break(c) || is_cjk_terminator(c) {
+                Some(i + c.len_utf8()).filter(|&end| end < text.len())
+            } else {
+                None
+            }
         })
-        .map(|((i, _), _)| i + 1)
-        .next()
+}
+
+/// Byte index just past the last point where the interim text may be frozen (a space, or a CJK break).
+fn find_last_freeze_point(text: &str) -> Option<usize> {
+    text.char_indices()
+        .rev()
+        .find(|&(_, c)| c.is_whitespace() || is_cjk_clause_break(c) || is_cjk_terminator(c))
+        .map(|(i, c)| i + c.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soniox::clock::ManualClock;
+    use crate::soniox::transcribe_mode::TranscribeMode;
+    use crate::soniox::translate_mode::TranslateMode;
+    use crate::types::soniox::{TranslateDisplay, TranslationType};
+
+    fn state_with_clock() -> (TranscriptionState, ManualClock) {
+        let clock = ManualClock::new();
+        let state = TranscriptionState::with_clock(50, 100, Box::new(clock.clone()));
+        (state, clock)
+    }
+
+    fn interim(text: &str) -> SonioxTranscriptionToken {
+        SonioxTranscriptionToken {
+            text: text.to_string(),
+            confidence: 1.0,
+            ..Default::default()
+        }
+    }
+
+    fn final_token(text: &str, start_ms: f64, end_ms: f64) -> SonioxTranscriptionToken {
+        SonioxTranscriptionToken {
+            text: text.to_string(),
+            start_ms: Some(start_ms),
+            end_ms: Some(end_ms),
+            confidence: 1.0,
+            is_final: true,
+            ..Default::default()
+        }
+    }
+
+    fn translated(mut token: SonioxTranscriptionToken) -> SonioxTranscriptionToken {
+        token.translation_status = Some("translation".to_string());
+        token
+    }
+
+    fn response(tokens: Vec<SonioxTranscriptionToken>, total_audio_proc_ms: f64) -> SonioxTranscriptionResponse {
+        SonioxTranscriptionResponse {
+            tokens,
+            total_audio_proc_ms,
+            ..Default::default()
+        }
+    }
+
+    /// Finalized blocks, oldest first.
+    fn block_texts(state: &TranscriptionState) -> Vec<String> {
+        state.finishes_lines.iter().rev().map(|line| line.text.clone()).collect()
+    }
+
+    /// Feeds one state scripted responses through a mode, on a clock the test moves by hand.
+    struct Harness {
+        state: TranscriptionState,
+        clock: ManualClock,
+        mode: Box<dyn SonioxMode>,
+    }
+
+    impl Harness {
+        fn new(mode: impl SonioxMode + 'static) -> Self {
+            let (state, clock) = state_with_clock();
+            Self { state, clock, mode: Box::new(mode) }
+        }
+
+        fn send(&mut self, tokens: Vec<SonioxTranscriptionToken>) -> &mut Self {
+            self.mode.process_event(&mut self.state, response(tokens, 0.0));
+            self
+        }
+
+        fn interim(&mut self, text: &str) -> &mut Self {
+            self.send(vec![interim(text)])
+        }
+
+        /// One response finalizing `(text, start_ms, end_ms)` in order.
+        fn finals(&mut self, finals: &[(&str, f64, f64)]) -> &mut Self {
+            self.send(finals.iter().map(|&(text, start_ms, end_ms)| final_token(text, start_ms, end_ms)).collect())
+        }
+
+        fn advance(&mut self, ms: u64) -> &mut Self {
+            self.clock.advance(Duration::from_millis(ms));
+            self
+        }
+
+        fn blocks(&self) -> Vec<String> {
+            block_texts(&self.state)
+        }
+
+        fn interim_text(&self) -> &str {
+            &self.state.interim_line.text
+        }
+    }
+
+    #[test]
+    fn conflicting_final_backtracks_frozen_interim() {
+        let mut h = Harness::new(TranscribeMode);
+
+        h.interim("Hello there. How");
+        assert_eq!(h.blocks(), ["Hello there."]);
+        assert_eq!(h.state.frozen_blocks_count, 1);
+        assert_eq!(h.interim_text(), " How");
+
+        h.interim("Hello there. How are you");
+        assert_eq!(h.blocks(), ["Hello there."]);
+        assert_eq!(h.interim_text(), " How are you");
+
+        h.finals(&[("Hello their.", 0.0, 600.0), (" How are you?", 600.0, 1200.0)]);
+        assert_eq!(h.blocks(), ["Hello their.", " How are you?"]);
+        assert_eq!(h.state.frozen_blocks_count, 0);
+        assert!(h.state.frozen_interim_history.is_empty());
+        assert!(h.interim_text().is_empty());
+    }
+
+    #[test]
+    fn conflicting_final_replaces_every_frozen_block() {
+        let mut h = Harness::new(TranscribeMode);
+
+        h.interim("One. Two. Three");
+        h.interim("One. Two. Three four");
+        assert_eq!(h.blocks(), ["One.", " Two."]);
+        assert_eq!(h.state.frozen_blocks_count, 2);
+
+        // Only the first sentence changed, but both frozen blocks give way to the finals.
+        h.send(vec![final_token("Won.", 0.0, 300.0), final_token(" Two.", 300.0, 600.0), interim(" Three four")]);
+        assert_eq!(h.blocks(), ["Won.", " Two."]);
+        assert_eq!(h.state.frozen_blocks_count, 0);
+        assert_eq!(h.interim_text(), " Three four");
+
+        // Freezing picks up again on the interim that follows the finals.
+        h.interim(" Three four five. Six");
+        assert_eq!(h.blocks(), ["Won.", " Two.", " Three four five."]);
+        assert_eq!(h.state.frozen_blocks_count, 1);
+        assert_eq!(h.interim_text(), " Six");
+    }
+
+    #[test]
+    fn matching_final_pushes_only_the_new_suffix() {
+        let mut h = Harness::new(TranscribeMode);
+
+        h.interim("Hello there. How").interim("Hello there. How are");
+        h.finals(&[("Hello there.", 0.0, 600.0), (" How are you?", 600.0, 1200.0)]);
+        assert_eq!(h.blocks(), ["Hello there.", " How are you?"]);
+        assert!(h.state.frozen_interim_history.is_empty());
+    }
+
+    #[test]
+    fn splits_accented_text_by_chars_on_char_boundaries() {
+        let sentence = "Árvíztűrő tükörfúrógép.";
+        let text = format!("{} Öt szép szűz lány", sentence);
+        let chars = sentence.chars().count();
+        assert!(sentence.len() > chars);
+
+        let idx = find_sentence_split(&text, chars).expect("terminator within the limit");
+        assert!(text.is_char_boundary(idx));
+        assert_eq!(&text[..idx], sentence);
+        // The limit counts chars: one short of the terminator finds nothing, though it is far below the byte length.
+        assert_eq!(find_sentence_split(&text, chars - 1), None);
+
+        let run = "Öt szép szűz lány őrült írót nyúz";
+        let idx = find_overflow_split(run, 9).expect("whitespace after the limit");
+        assert!(run.is_char_boundary(idx));
+        assert_eq!(&run[..idx], "Öt szép szűz");
+    }
+
+    #[test]
+    fn flickering_interim_is_frozen_only_once_stable() {
+        let mut h = Harness::new(TranscribeMode);
+        h.state.set_stability_params(true, 500);
+
+        for text in ["Hello there. How", "Hello their. How", "Hello there. How"] {
+            h.interim(text);
+            assert!(h.blocks().is_empty(), "froze '{}' while it was still changing", text);
+            h.advance(300);
+        }
+
+        // "Hello there." has now been proposed unchanged for 300 ms.
+        h.interim("Hello there. How are");
+        assert!(h.blocks().is_empty());
+
+        h.advance(200).interim("Hello there. How are you");
+        assert_eq!(h.blocks(), ["Hello there."]);
+        assert_eq!(h.interim_text(), " How are you");
+    }
+
+    #[test]
+    fn control_markers_never_reach_the_screen() {
+        let modes: [Box<dyn SonioxMode>; 2] = [
+            Box::new(TranscribeMode),
+            Box::new(TranslateMode::new(TranslationType::OneWay, TranslateDisplay::TranslationOnly, LanguageHint::English)),
+        ];
+        for mode in &modes {
+            let (mut state, _clock) = state_with_clock();
+            let tokens = vec![
+                translated(final_token("Hello.", 0.0, 400.0)),
+                translated(final_token("<end>", 400.0, 400.0)),
+                translated(interim("<fin>")),
+            ];
+            mode.process_event(&mut state, response(tokens, 0.0));
+            mode.process_event(&mut state, response(vec![translated(final_token("<fin>", 500.0, 500.0))], 0.0));
+
+            assert_eq!(block_texts(&state), ["Hello."]);
+            assert!(state.interim_line.text.is_empty());
+        }
+    }
+
+    #[test]
+    fn replay_after_reconnect_is_dropped_only_inside_the_window() {
+        let (mut state, clock) = state_with_clock();
+
+        TranscribeMode.process_event(&mut state, response(vec![final_token("Good morning.", 0.0, 800.0)], 5000.0));
+        // The new session's clock starts over and it finalizes the last segment again.
+        TranscribeMode.process_event(&mut state, response(vec![final_token("Good morning.", 0.0, 800.0)], 1000.0));
+        assert!(state.session_restarted.is_some());
+        assert_eq!(block_texts(&state), ["Good morning."]);
+
+        // Once the window has passed, saying it again is a real repeat.
+        clock.advance(REPLAY_WINDOW);
+        TranscribeMode.process_event(&mut state, response(vec![final_token(" Good morning.", 900.0, 1700.0)], 2000.0));
+        assert_eq!(block_texts(&state), ["Good morning.", " Good morning."]);
+    }
+
+    #[test]
+    fn smart_delay_holds_responses_until_the_clock_passes_it() {
+        let (mut state, clock) = state_with_clock();
+        state.set_smart_delay(300);
+
+        TranscribeMode.handle_incoming(&mut state, response(vec![final_token("Hi.", 0.0, 300.0)], 0.0));
+        clock.advance(Duration::from_millis(299));
+        state.process_pending_events(&TranscribeMode);
+        assert!(state.finishes_lines.is_empty());
+
+        clock.advance(Duration::from_millis(1));
+        state.process_pending_events(&TranscribeMode);
+        assert_eq!(block_texts(&state), ["Hi."]);
+    }
+
+    #[test]
+    fn word_filter_masks_the_interim_line() {
+        let (mut state, _clock) = state_with_clock();
+        state.set_word_filter(&["szűz".to_string()]);
+
+        TranscribeMode.process_event(&mut state, response(vec![interim("Öt szép szűz lány")], 0.0));
+        assert_eq!(state.visible_text(), "Öt szép **** lány");
+        // Freezing and dedup keep working on the text Soniox sent.
+        assert_eq!(state.interim_line.text, "Öt szép szűz lány");
+    }
+
+    #[test]
+    fn word_filter_masks_words_split_across_tokens() {
+        let mut h = Harness::new(TranscribeMode);
+        h.state.set_word_filter(&["fudge".to_string()]);
+        h.state.set_animation_speed(0, RevealMode::Typewriter);
+
+        h.send(vec![interim("Oh fu"), interim("dge")]);
+        assert_eq!(h.state.visible_text(), "Oh *****");
+
+        h.finals(&[("Oh fu", 0.0, 300.0)]);
+        h.state.update_animation(&TranscribeMode);
+        assert_eq!(h.blocks(), ["Oh fu"]);
+
+        // The rest of the word arrives in the next response and merges into the same block.
+        h.finals(&[("dge.", 300.0, 500.0)]);
+        assert_eq!(h.blocks(), ["Oh *****."]);
+        assert_eq!(h.state.finishes_lines[0].displayed_text, "Oh **");
+        h.state.update_animation(&TranscribeMode);
+        assert_eq!(h.state.visible_text(), "Oh *****.");
+    }
 }
@@ -3,183 +3,555 @@ use crate::soniox::URL;
 use crate::soniox::modes::SonioxMode;
 use crate::soniox::transcribe_mode::TranscribeMode;
 use crate::soniox::translate_mode::TranslateMode;
-use crate::types::audio::AudioMessage;
-use crate::types::settings::SettingsApp;
-use crate::types::soniox::SonioxTranscriptionResponse;
+use crate::types::audio::{AudioMessage, CHANNELS, LimiterMode, SAMPLE_RATE};
+use crate::types::languages::LanguageHint;
+use crate::types::settings::{MAX_CONTEXT_BYTES, SettingsApp};
+use crate::types::soniox::{ControlMsg, SonioxErrorMessage, SonioxMessage, SonioxTranscriptionResponse, StreamStatus};
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-use tokio_tungstenite::connect_async;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
 use tungstenite::client::IntoClientRequest;
 use tungstenite::{Bytes, Message, Utf8Bytes};
 use std::fs::OpenOptions;
 use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-async fn listen_soniox_stream(
-    bytes: Vec<u8>,
-    tx_transcription: UnboundedSender<SonioxTranscriptionResponse>,
-    mut rx_audio: UnboundedReceiver<AudioMessage>,
-    enable_raw_logging: bool,
-) -> Result<(), SonioxWindowsErrors> {
-    log::debug!("listen_soniox_stream: START");
-    'stream: loop {
-        log::debug!("listen_soniox_stream: Connecting to URL...");
-        let url = URL.into_client_request()?;
-        let (ws_stream, _) = match connect_async(url).await {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!("listen_soniox_stream: Connect FAILED: {:?}", e);
-                return Err(SonioxWindowsErrors::Internal(e.to_string()));
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(4);
+/// After the end-of-audio frame, how long to wait for the last tokens and the server's close.
+const FINAL_RESPONSE_TIMEOUT: Duration = Duration::from_secs(3);
+/// With LimiterMode::Soft, samples below this level pass through unchanged.
+const SOFT_LIMIT_KNEE: f32 = 0.5;
+/// A connection without audio from the capture for this long is reported as NoAudio.
+const NO_AUDIO_TIMEOUT: Duration = Duration::from_secs(2);
+/// Soniox ends a session that gets no audio for a while; while paused this control message keeps it open.
+const PAUSED_KEEPALIVE_MESSAGE: &str = r#"{"type":"keepalive"}"#;
+const PAUSED_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+type SonioxWrite = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+type SonioxRead = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+/// Per-connection flags shared between the reader task and the audio loop.
+#[derive(Default)]
+struct ConnectionHealth {
+    received_any: AtomicBool,
+    awaiting_pong: AtomicBool,
+}
+
+/// Converts samples to little-endian 16-bit PCM. This is the only place audio is limited;
+/// the capture side leaves anything beyond full scale to it.
+fn to_pcm16(samples: &[f32], limiter: LimiterMode) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(samples.len() * 2);
+    for &s in samples {
+        let limited = match limiter {
+            LimiterMode::Hard => s.clamp(-1.0, 1.0),
+            // Continuous with slope 1 at the knee, approaching (never reaching) full scale.
+            LimiterMode::Soft if s.abs() > SOFT_LIMIT_KNEE => {
+                let headroom = 1.0 - SOFT_LIMIT_KNEE;
+                s.signum() * (SOFT_LIMIT_KNEE + headroom * ((s.abs() - SOFT_LIMIT_KNEE) / headroom).tanh())
             }
+            LimiterMode::Soft => s,
         };
-        log::debug!("listen_soniox_stream: Connected!");
-        
-        let (mut write, mut read) = ws_stream.split();
-        let json_str = String::from_utf8_lossy(&bytes);
-        log::debug!("listen_soniox_stream: Sending JSON: {}", json_str);
-        if let Err(e) = write.send(Message::Text(Utf8Bytes::try_from(bytes.clone())?)).await {
-             log::error!("listen_soniox_stream: Failed to send initial JSON: {:?}", e);
-             return Err(SonioxWindowsErrors::Internal(e.to_string()));
+        bytes.extend_from_slice(&((limited * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    bytes
+}
+
+/// Sends status changes to the overlay, skipping repeats.
+struct StatusReporter {
+    tx: UnboundedSender<StreamStatus>,
+    last: Option<StreamStatus>,
+}
+
+impl StatusReporter {
+    fn set(&mut self, status: StreamStatus) {
+        if self.last != Some(status) {
+            self.last = Some(status);
+            let _ = self.tx.send(status);
         }
-        log::debug!("listen_soniox_stream: Initial JSON Sent.");
-
-        let tx_subs = tx_transcription.clone();
-        let reader = async move {
-            log::debug!("listen_soniox_stream: Reader Task Started.");
-            while let Some(msg) = read.next().await {
-                match msg {
-                     Ok(Message::Text(txt)) => {
-                        log::debug!("Received Soniox Message: {}", txt);
-                        // Log raw raw data to file
-                        if enable_raw_logging {
-                            if let Ok(mut file) = OpenOptions::new()
-                                .create(true)
-                                .append(true)
-                                .open("raw_data.log") 
-                            {
-                                let _ = writeln!(file, "{}", txt);
-                            }
-                        }
+    }
+}
 
-                        if let Ok(response) = serde_json::from_str::<SonioxTranscriptionResponse>(&txt) {
-                             let _ = tx_subs.send(response);
-                        } else {
-                             log::warn!("Failed to parse Soniox response: {}", txt);
-                        }
-                     },
-                     Ok(Message::Close(c)) => {
-                         log::debug!("listen_soniox_stream: Server sent CLOSE: {:?}", c);
-                         break;
-                     },
-                     Err(e) => {
-                         log::error!("listen_soniox_stream: Read Error: {:?}", e);
-                         break;
-                     }
-                     _ => {} // Ignore Ping/Pong/Binary
+enum ConnectionOutcome {
+    Finished,
+    Lost(SonioxWindowsErrors),
+    /// Reconnect right away with a request for this model.
+    SwitchModel(String),
+}
+
+async fn connect_soniox(bytes: &[u8]) -> Result<(SonioxWrite, SonioxRead), SonioxWindowsErrors> {
+    log::debug!("listen_soniox_stream: Connecting to URL...");
+    let url = URL.into_client_request()?;
+    let (ws_stream, _) = match connect_async(url).await {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!("listen_soniox_stream: Connect FAILED: {:?}", e);
+            return Err(SonioxWindowsErrors::Internal(e.to_string()));
+        }
+    };
+    log::debug!("listen_soniox_stream: Connected!");
+
+    let (mut write, read) = ws_stream.split();
+    let json_str = String::from_utf8_lossy(bytes);
+    log::debug!("listen_soniox_stream: Sending JSON: {}", json_str);
+    if let Err(e) = write.send(Message::Text(Utf8Bytes::try_from(bytes.to_vec())?)).await {
+         log::error!("listen_soniox_stream: Failed to send initial JSON: {:?}", e);
+         return Err(SonioxWindowsErrors::Internal(e.to_string()));
+    }
+    log::debug!("listen_soniox_stream: Initial JSON Sent.");
+    Ok((write, read))
+}
+
+async fn read_soniox_stream(
+    mut read: SonioxRead,
+    tx_subs: UnboundedSender<SonioxTranscriptionResponse>,
+    tx_notice: UnboundedSender<String>,
+    enable_raw_logging: bool,
+    health: Arc<ConnectionHealth>,
+) -> Option<SonioxWindowsErrors> {
+    log::debug!("listen_soniox_stream: Reader Task Started.");
+    let mut close_error = None;
+    while let Some(msg) = read.next().await {
+        match msg {
+             Ok(Message::Text(txt)) => {
+                health.received_any.store(true, Ordering::Relaxed);
+                log::debug!("Received Soniox Message: {}", txt);
+                // Log raw raw data to file
+                if enable_raw_logging {
+                    if let Ok(mut file) = OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open("raw_data.log") 
+                    {
+                        let _ = writeln!(file, "{}", txt);
+                    }
                 }
-            }
-            log::debug!("listen_soniox_stream: Reader Task FINISHED (Socket closed?).");
-            <Result<(), SonioxWindowsErrors>>::Ok(())
-        };
 
-        tokio::spawn(async move {
-            let _ = reader
-                .await
-                .inspect_err(|err| log::error!("error during read message: {}", err));
-        });
-
-        log::debug!("listen_soniox_stream: Starting Audio Loop...");
-        while let Some(message) = rx_audio.recv().await {
-            match message {
-                AudioMessage::Audio(buffer) => {
-                    if buffer.is_empty() {
-                        log::warn!("listen_soniox_stream: Received EMPTY BUFFER. Breaking loop (Original Logic).");
-                        break;
+                match serde_json::from_str::<SonioxMessage>(&txt) {
+                    Ok(SonioxMessage::Response(response)) => {
+                        let _ = tx_subs.send(response);
                     }
-                    // Debug: Log every Nth packet to ensure flow? 
-                    // No, too spammy.
-                    
-                    let mut pcm16 = Vec::with_capacity(buffer.len() * 2);
-                    for s in buffer {
-                        let sample = (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
-                        pcm16.extend_from_slice(&sample.to_le_bytes());
+                    Ok(SonioxMessage::Error(error)) => {
+                        log::error!("listen_soniox_stream: Soniox error {:?}: {}", error.error_code, error.error_message);
+                        let _ = tx_notice.send(format!("Soniox: {}", error.error_message));
+                        // Soniox closes the session after an error; report it instead of a bare close.
+                        close_error = Some(api_error(error));
+                        break;
                     }
+                    Err(_) => log::warn!("Failed to parse Soniox response: {}", txt),
+                }
+             },
+             Ok(Message::Pong(_)) => {
+                 health.awaiting_pong.store(false, Ordering::Relaxed);
+             },
+             Ok(Message::Close(c)) => {
+                 log::debug!("listen_soniox_stream: Server sent CLOSE: {:?}", c);
+                 close_error = c.map(|frame| close_frame_error(u16::from(frame.code), frame.reason.as_str()));
+                 break;
+             },
+             Err(e) => {
+                 log::error!("listen_soniox_stream: Read Error: {:?}", e);
+                 break;
+             }
+             _ => {} // Ignore Ping/Binary
+        }
+    }
+    log::debug!("listen_soniox_stream: Reader Task FINISHED (Socket closed?).");
+    close_error
+}
+
+fn api_error(error: SonioxErrorMessage) -> SonioxWindowsErrors {
+    match error.error_code {
+        Some(401) => SonioxWindowsErrors::SonioxAuth(error.error_message),
+        code => SonioxWindowsErrors::SonioxApi { code: code.unwrap_or(0), message: error.error_message },
+    }
+}
+
+/// Client errors (bad key, bad request, no balance) won't go away by retrying the same request.
+/// 408 (request timeout) and 429 (rate limit) are transient and go through the reconnect backoff.
+fn is_fatal(err: &SonioxWindowsErrors) -> bool {
+    matches!(
+        err,
+        SonioxWindowsErrors::SonioxAuth(_) | SonioxWindowsErrors::SonioxApi { code: 400..=403, .. }
+    )
+}
+
+/// Maps the server's Close frame to an error, singling out a rejected API key.
+fn close_frame_error(code: u16, reason: &str) -> SonioxWindowsErrors {
+    let lowered = reason.to_lowercase();
+    let is_auth = ["api key", "api_key", "unauthorized", "authentication"]
+        .iter()
+        .any(|needle| lowered.contains(needle));
+    if is_auth {
+        SonioxWindowsErrors::SonioxAuth(reason.to_string())
+    } else {
+        SonioxWindowsErrors::SonioxServerClosed { code, reason: reason.to_string() }
+    }
+}
 
-                    let result = write.send(Message::Binary(Bytes::from(pcm16))).await;
-                    
-                    // Very verbose, but necessary for now
-                    // log::info!("listen_soniox_stream: Sent binary packet.");
+async fn forward_audio(
+    write: &mut SonioxWrite,
+    rx_audio: &mut UnboundedReceiver<AudioMessage>,
+    rx_control: &mut UnboundedReceiver<ControlMsg>,
+    reader: &mut JoinHandle<Option<SonioxWindowsErrors>>,
+    health: &ConnectionHealth,
+    keepalive_interval: Option<Duration>,
+    empty_audio_timeout: Option<Duration>,
+    pending: &mut Vec<u8>,
+    chunk_bytes: usize,
+    limiter: LimiterMode,
+    paused: &mut bool,
+    status: &mut StatusReporter,
+) -> ConnectionOutcome {
+    log::debug!("listen_soniox_stream: Starting Audio Loop...");
+    // Paused audio still counts: the capture is healthy, it just isn't sent.
+    status.set(StreamStatus::Live);
+    let mut last_audio = Instant::now();
+    // Start of the current run of empty buffers from the capture.
+    let mut empty_since = Option::<Instant>::None;
+    let mut audio_check = tokio::time::interval(NO_AUDIO_TIMEOUT / 2);
+    // Idle proxies drop silent websockets; a ping that isn't answered before the next one is due means the link is dead.
+    let mut keepalive = keepalive_interval
+        .map(|period| tokio::time::interval_at(tokio::time::Instant::now() + period, period));
+    // Fires right away after a pause begins (one catch-up tick), then every interval.
+    let mut paused_keepalive = tokio::time::interval(PAUSED_KEEPALIVE_INTERVAL);
+    paused_keepalive.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            message = rx_audio.recv() => match message {
+                Some(AudioMessage::Audio(_)) if *paused => {
+                    last_audio = Instant::now();
+                    status.set(StreamStatus::Live);
+                }
+                Some(AudioMessage::Audio(buffer)) if buffer.is_empty() => {
+                    // A stray empty chunk (e.g. from the mixer) is skipped; only the capture
+                    // delivering nothing else for `empty_audio_timeout` ends the stream.
+                    let since = *empty_since.get_or_insert_with(Instant::now);
+                    if empty_audio_timeout.is_some_and(|timeout| since.elapsed() >= timeout) {
+                        log::warn!("listen_soniox_stream: Only empty buffers for {:?}. Closing stream.", since.elapsed());
+                        return flush_pending(write, pending).await;
+                    }
+                }
+                Some(AudioMessage::Audio(buffer)) => {
+                    last_audio = Instant::now();
+                    empty_since = None;
+                    status.set(StreamStatus::Live);
+                    pending.extend_from_slice(&to_pcm16(&buffer, limiter));
 
-                    if let Err(err) = result {
-                        log::error!("listen_soniox_stream: error during sent binary -> {:?}. Reconnecting...", err);
-                        continue 'stream;
+                    // Coalesce tiny WASAPI packets into fewer, larger websocket frames.
+                    if pending.len() >= chunk_bytes {
+                        if let Err(err) = write.send(Message::Binary(Bytes::from(std::mem::take(pending)))).await {
+                            log::error!("listen_soniox_stream: error during sent binary -> {:?}", err);
+                            return ConnectionOutcome::Lost(err.into());
+                        }
                     }
                 }
-                AudioMessage::Stop => {
+                Some(AudioMessage::Stop) => {
                     log::debug!("listen_soniox_stream: Received STOP message. Closing stream.");
-                    let _ = write.send(Message::Binary(Bytes::new())).await;
-                    break 'stream;
+                    return flush_pending(write, pending).await;
+                }
+                None => {
+                    log::debug!("listen_soniox_stream: RX_AUDIO loop finished (Sender dropped).");
+                    return flush_pending(write, pending).await;
+                }
+            },
+            Some(control) = rx_control.recv() => match control {
+                ControlMsg::SetModel(model) => {
+                    log::info!("listen_soniox_stream: Switching model to '{}'", model);
+                    return ConnectionOutcome::SwitchModel(model);
+                }
+                ControlMsg::SetPaused(state) => {
+                    log::info!("listen_soniox_stream: {} audio", if state { "Pausing" } else { "Resuming" });
+                    *paused = state;
+                    pending.clear();
+                }
+            },
+            _ = audio_check.tick() => {
+                if last_audio.elapsed() >= NO_AUDIO_TIMEOUT {
+                    status.set(StreamStatus::NoAudio);
+                }
+            }
+            _ = paused_keepalive.tick(), if *paused => {
+                if let Err(err) = write.send(Message::Text(Utf8Bytes::from_static(PAUSED_KEEPALIVE_MESSAGE))).await {
+                    log::error!("listen_soniox_stream: error during paused keepalive -> {:?}", err);
+                    return ConnectionOutcome::Lost(err.into());
+                }
+            }
+            _ = async { keepalive.as_mut().expect("guarded").tick().await }, if keepalive.is_some() => {
+                if health.awaiting_pong.load(Ordering::Relaxed) {
+                    log::warn!("listen_soniox_stream: No Pong received for keepalive Ping.");
+                    return ConnectionOutcome::Lost(SonioxWindowsErrors::Internal(
+                        "Soniox keepalive timed out".to_string(),
+                    ));
+                }
+                health.awaiting_pong.store(true, Ordering::Relaxed);
+                if let Err(err) = write.send(Message::Ping(Bytes::new())).await {
+                    log::error!("listen_soniox_stream: error during keepalive ping -> {:?}", err);
+                    return ConnectionOutcome::Lost(err.into());
                 }
             }
+            closed = &mut *reader => {
+                return ConnectionOutcome::Lost(closed.ok().flatten().unwrap_or_else(|| {
+                    SonioxWindowsErrors::Internal("Soniox connection closed".to_string())
+                }));
+            }
         }
-        
-        log::debug!("listen_soniox_stream: RX_AUDIO loop finished (Sender dropped or Break).");
+    }
+}
 
-        let _ = write
-            .send(Message::Binary(Bytes::new()))
-            .await
-            .inspect_err(|err| log::error!("error during write message: {}", err));
-        break 'stream;
+/// Sends whatever audio is still buffered so the end of speech isn't lost, then reports the stream as finished.
+async fn flush_pending(write: &mut SonioxWrite, pending: &mut Vec<u8>) -> ConnectionOutcome {
+    if !pending.is_empty() {
+        if let Err(err) = write.send(Message::Binary(Bytes::from(std::mem::take(pending)))).await {
+            log::error!("listen_soniox_stream: error during final flush -> {:?}", err);
+            return ConnectionOutcome::Lost(err.into());
+        }
+    }
+    ConnectionOutcome::Finished
+}
+
+async fn listen_soniox_stream(
+    build_request: impl Fn(&str) -> Result<Vec<u8>, SonioxWindowsErrors>,
+    model: &str,
+    tx_transcription: UnboundedSender<SonioxTranscriptionResponse>,
+    tx_notice: UnboundedSender<String>,
+    tx_status: UnboundedSender<StreamStatus>,
+    mut rx_audio: UnboundedReceiver<AudioMessage>,
+    mut rx_control: UnboundedReceiver<ControlMsg>,
+    enable_raw_logging: bool,
+    max_reconnects: u32,
+    keepalive_interval: Option<Duration>,
+    empty_audio_timeout: Option<Duration>,
+    chunk_ms: u64,
+    limiter: LimiterMode,
+) -> Result<(), SonioxWindowsErrors> {
+    log::debug!("listen_soniox_stream: START");
+    // 16-bit PCM: two bytes per sample.
+    let chunk_bytes = (SAMPLE_RATE as u64 * CHANNELS as u64 * 2 * chunk_ms / 1000) as usize;
+    // Kept across reconnects so audio captured during a switch isn't dropped.
+    let mut pending = Vec::with_capacity(chunk_bytes * 2);
+    let mut reconnects: u32 = 0;
+    let mut paused = false;
+    let mut backoff = INITIAL_BACKOFF;
+    let mut bytes = build_request(model)?;
+    let mut status = StatusReporter { tx: tx_status, last: None };
+
+    loop {
+        status.set(StreamStatus::Disconnected);
+        let health = Arc::new(ConnectionHealth::default());
+        let outcome = match connect_soniox(&bytes).await {
+            Ok((mut write, read)) => {
+                let mut reader = tokio::spawn(read_soniox_stream(
+                    read,
+                    tx_transcription.clone(),
+                    tx_notice.clone(),
+                    enable_raw_logging,
+                    health.clone(),
+                ));
+                let outcome = forward_audio(
+                    &mut write,
+                    &mut rx_audio,
+                    &mut rx_control,
+                    &mut reader,
+                    &health,
+                    keepalive_interval,
+                    empty_audio_timeout,
+                    &mut pending,
+                    chunk_bytes,
+                    limiter,
+                    &mut paused,
+                    &mut status,
+                )
+                .await;
+                match outcome {
+                    ConnectionOutcome::Finished => {
+                        let _ = write
+                            .send(Message::Binary(Bytes::new()))
+                            .await
+                            .inspect_err(|err| log::error!("error during write message: {}", err));
+                        // Soniox finalizes what's left, sends it and closes; the reader forwards it before ending.
+                        if tokio::time::timeout(FINAL_RESPONSE_TIMEOUT, &mut reader).await.is_err() {
+                            log::warn!("listen_soniox_stream: No close from Soniox after end of audio; giving up.");
+                            reader.abort();
+                        }
+                    }
+                    ConnectionOutcome::SwitchModel(_) => {
+                        let _ = write
+                            .close()
+                            .await
+                            .inspect_err(|err| log::error!("error during close: {}", err));
+                        reader.abort();
+                    }
+                    ConnectionOutcome::Lost(_) => reader.abort(),
+                }
+                outcome
+            }
+            Err(err) => ConnectionOutcome::Lost(err),
+        };
+
+        let err = match outcome {
+            ConnectionOutcome::Finished => break,
+            ConnectionOutcome::SwitchModel(model) => {
+                bytes = build_request(&model)?;
+                reconnects = 0;
+                backoff = INITIAL_BACKOFF;
+                continue;
+            }
+            ConnectionOutcome::Lost(err) if is_fatal(&err) => {
+                log::error!("listen_soniox_stream: {}", err);
+                return Err(err);
+            }
+            ConnectionOutcome::Lost(err) => err,
+        };
+
+        // A connection that delivered responses was healthy; start counting afresh.
+        if health.received_any.load(Ordering::Relaxed) {
+            reconnects = 0;
+            backoff = INITIAL_BACKOFF;
+        }
+        reconnects += 1;
+        if reconnects > max_reconnects {
+            log::error!("listen_soniox_stream: Giving up after {} reconnect attempts", max_reconnects);
+            return Err(err);
+        }
+        log::warn!(
+            "listen_soniox_stream: Connection lost ({}). Reconnect {}/{} in {:?}...",
+            err, reconnects, max_reconnects, backoff
+        );
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
     }
 
     log::debug!("listen_soniox_stream: RETURNING Ok. Stream Ended.");
     Ok(())
 }
 
+/// Runs one Soniox session. `target_language` is the one-way translation target, so that
+/// several sessions can share `settings` with a language each.
 pub async fn start_soniox_stream(
     settings: &SettingsApp,
+    target_language: LanguageHint,
     tx_transcription: UnboundedSender<SonioxTranscriptionResponse>,
+    tx_notice: UnboundedSender<String>,
+    tx_status: UnboundedSender<StreamStatus>,
     rx_audio: UnboundedReceiver<AudioMessage>,
+    rx_control: UnboundedReceiver<ControlMsg>,
 ) -> Result<(), SonioxWindowsErrors> {
     // START OF REFACTOR: Select Mode
     
     // Determine Audio Format (The "Deep Research" Fix)
-    // We lift this logic OUT of the mode and OUT of the request builder.
-    // It is now strictly decided here before any request is formed.
-    let (sample_rate, channels) = if settings.audio_input().trim() == "both" {
-        log::debug!("start_soniox_stream: 'both' mode detected -> Forcing 16000Hz Mono");
-        (16000, 1)
-    } else {
-         use wasapi::{DeviceEnumerator, Direction, initialize_mta};
-         let _ = initialize_mta().ok();
-         let enumerator = DeviceEnumerator::new()?;
-         let direction = if settings.audio_input() == "microphone" {
-            Direction::Capture
-        } else {
-            Direction::Render
-        };
-        let device = enumerator.get_default_device(&direction)?;
-        let audio_client = device.get_iaudioclient()?;
-        let format = audio_client.get_mixformat()?;
-        let sr = format.get_samplespersec();
-        let ch = format.get_nchannels();
-        log::info!("start_soniox_stream: Single device mode -> Detected {}Hz {}ch", sr, ch);
-        (sr, ch)
-    };
+    // Every capture path (single, dual) resamples to 16kHz mono before sending,
+    // so the advertised format no longer depends on the device's mix format.
+    let (sample_rate, channels) = (SAMPLE_RATE, CHANNELS);
+    log::debug!("start_soniox_stream: Advertising {}Hz {}ch", sample_rate, channels);
+    if let Some((path, file_bytes)) = settings.context_file() {
+        log::info!(
+            "Using context from '{}': sending {} of {} bytes",
+            path, settings.context().len(), file_bytes
+        );
+        if file_bytes > MAX_CONTEXT_BYTES {
+            log::warn!("context_file is larger than {} bytes and was truncated", MAX_CONTEXT_BYTES);
+        }
+    }
     
     let audio_format = (sample_rate, channels);
 
-    let request = if settings.enable_translate() {
-        let mode = TranslateMode;
-        mode.create_request(settings, audio_format)?
-    } else {
-        let mode = TranscribeMode;
-        mode.create_request(settings, audio_format)?
+    // Rebuilt whenever the model is switched mid-session.
+    let build_request = |model: &str| -> Result<Vec<u8>, SonioxWindowsErrors> {
+        let mut request = if settings.enable_translate() {
+            let mode = TranslateMode::new(settings.translation_type(), settings.translate_display(), target_language);
+            mode.create_request(settings, audio_format)?
+        } else {
+            let mode = TranscribeMode;
+            mode.create_request(settings, audio_format)?
+        };
+        request.model = model;
+        Ok(serde_json::to_vec(&request)?)
     };
     // END OF REFACTOR
 
-    let bytes = serde_json::to_vec(&request)?;
-
-    log::debug!("Started Soniox stream!");
+    log::info!("Started Soniox stream (session id: {})", settings.session_id());
     log::debug!("Starting to listen websocket stream Soniox...");
-    listen_soniox_stream(bytes, tx_transcription, rx_audio, settings.enable_raw_logging()).await
+    let result = listen_soniox_stream(
+        build_request,
+        settings.model(),
+        tx_transcription,
+        tx_notice,
+        tx_status.clone(),
+        rx_audio,
+        rx_control,
+        settings.enable_raw_logging(),
+        settings.max_reconnects(),
+        settings.keepalive_interval(),
+        settings.empty_audio_timeout(),
+        settings.audio_chunk_ms(),
+        settings.limiter(),
+    )
+    .await;
+    if result.is_err() {
+        let _ = tx_status.send(StreamStatus::Disconnected);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pcm(samples: &[f32], limiter: LimiterMode) -> Vec<i16> {
+        to_pcm16(samples, limiter)
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect()
+    }
+
+    fn api(code: u16) -> SonioxWindowsErrors {
+        SonioxWindowsErrors::SonioxApi { code, message: String::new() }
+    }
+
+    #[test]
+    fn only_permanent_client_errors_are_fatal() {
+        assert!(is_fatal(&SonioxWindowsErrors::SonioxAuth(String::new())));
+        for code in [400, 401, 402, 403] {
+            assert!(is_fatal(&api(code)), "{} should end the stream", code);
+        }
+        for code in [408, 429, 500, 503] {
+            assert!(!is_fatal(&api(code)), "{} should be retried", code);
+        }
+        assert!(!is_fatal(&SonioxWindowsErrors::SonioxServerClosed { code: 1006, reason: String::new() }));
+    }
+
+    #[test]
+    fn hard_limiter_clamps_to_full_scale() {
+        let out = pcm(&[1.0, -1.0, 1.5, -3.0, 0.25], LimiterMode::Hard);
+        assert_eq!(out, [i16::MAX, -i16::MAX, i16::MAX, -i16::MAX, i16::MAX / 4]);
+    }
+
+    #[test]
+    fn soft_limiter_rounds_off_peaks_without_wrapping() {
+        let out = pcm(&[1.0, -1.0, 1.5, 10.0, -10.0], LimiterMode::Soft);
+        assert!(out[0] > (SOFT_LIMIT_KNEE * i16::MAX as f32) as i16 && out[0] < i16::MAX);
+        assert_eq!(out[1], -out[0]);
+        // Above full scale the curve keeps rising towards, and never past, i16::MAX.
+        assert!(out[2] > out[0]);
+        assert!(out[3] >= out[2] && out[3] <= i16::MAX);
+        assert!(out[4] < 0 && out[4] >= -i16::MAX);
+    }
+
+    #[test]
+    fn both_limiters_are_continuous_at_the_knee() {
+        let below = SOFT_LIMIT_KNEE - 1e-5;
+        let above = SOFT_LIMIT_KNEE + 1e-5;
+        for limiter in [LimiterMode::Hard, LimiterMode::Soft] {
+            let out = pcm(&[below, SOFT_LIMIT_KNEE, above, -below, -above], limiter);
+            assert!((out[2] - out[0]).abs() <= 1, "{:?} jumps at the knee: {:?}", limiter, out);
+            assert!((out[4] - out[3]).abs() <= 1, "{:?} jumps at the knee: {:?}", limiter, out);
+            assert_eq!(out[1], (SOFT_LIMIT_KNEE * i16::MAX as f32) as i16);
+        }
+    }
 }
@@ -1,6 +1,12 @@
 use crate::errors::SonioxWindowsErrors;
 use crate::types::settings::SettingsApp;
-use serde::Deserialize;
+use crate::types::soniox::TranslationType;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MODELS_CACHE_FILE: &str = "models_cache.json";
+/// Re-fetch the models list once the cache is older than this.
+const CACHE_MAX_AGE_SECS: u64 = 24 * 60 * 60;
 
 #[derive(Debug, Deserialize)]
 struct Model {
@@ -12,47 +18,189 @@ struct ModelsResponse {
     models: Vec<Model>,
 }
 
+/// Catches translation setups that can't produce useful output before we connect.
+pub fn validate_languages(settings: &SettingsApp) -> Result<(), SonioxWindowsErrors> {
+    if !settings.enable_translate() {
+        return Ok(());
+    }
+
+    let hints = settings.language_hints();
+    if hints.is_empty() {
+        return Err(SonioxWindowsErrors::Internal(
+            "Translation is enabled but `language_hints` is empty.\nList the languages being spoken, e.g. language_hints = [\"en\"].".to_string(),
+        ));
+    }
+
+    if settings.translation_type() == TranslationType::OneWay {
+        let target = settings.target_language();
+        if hints == [target] {
+            return Err(SonioxWindowsErrors::Internal(format!(
+                "`target_language` ({}) is the only language in `language_hints`, so there is nothing to translate.\nChange `target_language`, add the spoken language to `language_hints`, or set enable_translate = false.",
+                target.code()
+            )));
+        }
+    }
+    Ok(())
+}
+
 pub fn validate_model(settings: &SettingsApp) -> Result<(), SonioxWindowsErrors> {
     log::info!("Validating model '{}'...", settings.model());
+    let configured_model = settings.model();
+    let fingerprint = key_fingerprint(settings.api_key());
+    let cache = load_cache().filter(|cache| cache.key_fingerprint == fingerprint);
+
+    if let Some(cache) = &cache {
+        let fresh = unix_now().saturating_sub(cache.fetched_at) < CACHE_MAX_AGE_SECS;
+        if fresh && cache.models.iter().any(|m| m == configured_model) {
+            log::info!("Model '{}' is valid (cached list).", configured_model);
+            return Ok(());
+        }
+    }
+
+    let models = match fetch_models(settings.api_key()) {
+        Ok(models) => {
+            save_cache(&ModelsCache { fetched_at: unix_now(), key_fingerprint: fingerprint, models: models.clone() });
+            models
+        }
+        Err(FetchError::Network(e)) => match cache {
+            Some(cache) if cache.models.iter().any(|m| m == configured_model) => {
+                log::warn!("Could not reach Soniox to refresh the models list ({}). Using the cached list.", e);
+                return Ok(());
+            }
+            _ => return Err(SonioxWindowsErrors::Internal(e)),
+        },
+        Err(FetchError::Rejected(e)) => return Err(e),
+    };
 
+    if models.iter().any(|m| m == configured_model) {
+        log::info!("Model '{}' is valid.", configured_model);
+        Ok(())
+    } else {
+        let available = realtime_models(&models);
+        log::error!("Invalid model configured: {}. Available (RT): {:?}", configured_model, available);
+        Err(SonioxWindowsErrors::Internal(format!(
+            "Invalid model configured: '{}'.\nAvailable Real-Time models: {}",
+            configured_model,
+            available.join(", ")
+        )))
+    }
+}
+
+/// Checks `api_key` against Soniox without blocking the caller and returns the real-time models it can use.
+/// A successful check refreshes the models cache for that key.
+pub async fn test_api_key(api_key: String) -> Result<Vec<String>, SonioxWindowsErrors> {
+    let result = tokio::task::spawn_blocking(move || {
+        let models = fetch_models(&api_key)?;
+        save_cache(&ModelsCache { fetched_at: unix_now(), key_fingerprint: key_fingerprint(&api_key), models: models.clone() });
+        Ok(models)
+    })
+    .await
+    .map_err(|e| SonioxWindowsErrors::Internal(e.to_string()))?;
+
+    match result {
+        Ok(models) => Ok(realtime_models(&models).into_iter().map(str::to_string).collect()),
+        Err(FetchError::Network(e)) => Err(SonioxWindowsErrors::Internal(format!("Could not reach Soniox: {}", e))),
+        Err(FetchError::Rejected(e)) => Err(e),
+    }
+}
+
+/// Real-time models from the cached list for `api_key`, regardless of age. Empty if nothing is cached.
+pub fn cached_realtime_models(api_key: &str) -> Vec<String> {
+    load_cache()
+        .filter(|cache| cache.key_fingerprint == key_fingerprint(api_key))
+        .map(|cache| realtime_models(&cache.models).into_iter().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Model ids usable for streaming; Soniox marks them with `-rt-`.
+fn realtime_models(models: &[String]) -> Vec<&str> {
+    models.iter().map(|m| m.as_str()).filter(|id| id.contains("-rt-")).collect()
+}
+
+enum FetchError {
+    /// Soniox couldn't be reached; a cached list may still be used.
+    Network(String),
+    /// Soniox answered, but refused the request (e.g. a bad API key).
+    Rejected(SonioxWindowsErrors),
+}
+
+fn fetch_models(api_key: &str) -> Result<Vec<String>, FetchError> {
     let client = reqwest::blocking::Client::new();
     let response = client
         .get("https://api.soniox.com/v1/models")
-        .header("Authorization", format!("Bearer {}", settings.api_key()))
+        .header("Authorization", format!("Bearer {}", api_key))
         .send()
-        .map_err(|e| SonioxWindowsErrors::Internal(e.to_string()))?;
+        .map_err(|e| FetchError::Network(e.to_string()))?;
 
     let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(FetchError::Rejected(SonioxWindowsErrors::SonioxAuth(response.text().unwrap_or_default())));
+    }
     if !status.is_success() {
-        return Err(SonioxWindowsErrors::Internal(format!(
+        return Err(FetchError::Rejected(SonioxWindowsErrors::Internal(format!(
             "Failed to fetch models: {} (Status: {})",
             response.text().unwrap_or_default(),
             status
-        )));
+        ))));
     }
 
     let models_resp: ModelsResponse = response.json().map_err(|e| {
-        SonioxWindowsErrors::Internal(format!("Failed to parse models response: {}", e))
+        FetchError::Rejected(SonioxWindowsErrors::Internal(format!("Failed to parse models response: {}", e)))
     })?;
+    Ok(models_resp.models.into_iter().map(|m| m.id).collect())
+}
 
-    let configured_model = settings.model();
-    let exists = models_resp.models.iter().any(|m| m.id == configured_model);
+/// Last successful models list, so launches don't block on (or fail without) the network.
+#[derive(Debug, Serialize, Deserialize)]
+struct ModelsCache {
+    fetched_at: u64,
+    /// Hash of the API key the list was fetched with; a different key invalidates the cache.
+    key_fingerprint: u64,
+    models: Vec<String>,
+}
 
-    if exists {
-        log::info!("Model '{}' is valid.", configured_model);
-        Ok(())
-    } else {
-        let available: Vec<&str> = models_resp
-            .models
-            .iter()
-            .map(|m| m.id.as_str())
-            .filter(|id| id.contains("-rt-"))
-            .collect();
-        log::error!("Invalid model configured: {}. Available (RT): {:?}", configured_model, available);
-        Err(SonioxWindowsErrors::Internal(format!(
-            "Invalid model configured: '{}'.\nAvailable Real-Time models: {}",
-            configured_model,
-            available.join(", ")
-        )))
+fn load_cache() -> Option<ModelsCache> {
+    let data = std::fs::read_to_string(MODELS_CACHE_FILE).ok()?;
+    serde_json::from_str(&data)
+        .inspect_err(|e| log::warn!("Ignoring unreadable {}: {}", MODELS_CACHE_FILE, e))
+        .ok()
+}
+
+fn save_cache(cache: &ModelsCache) {
+    let result = serde_json::to_string(cache)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(MODELS_CACHE_FILE, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to write {}: {}", MODELS_CACHE_FILE, e);
+    }
+}
+
+/// 64-bit FNV-1a of the key. The value is stored in the cache file, so it must not change between
+/// builds the way std's DefaultHasher may.
+fn key_fingerprint(api_key: &str) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    api_key
+        .bytes()
+        .fold(FNV_OFFSET, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_fingerprint_is_fnv1a() {
+        // Published FNV-1a 64 test vectors; a changed value would invalidate every saved cache.
+        assert_eq!(key_fingerprint(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(key_fingerprint("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(key_fingerprint("foobar"), 0x8594_4171_f739_67e8);
     }
 }
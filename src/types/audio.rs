@@ -1,16 +1,93 @@
 pub type AudioSample = Vec<f32>;
 
+/// Format of every audio stream sent to Soniox, regardless of the capture device.
+pub const SAMPLE_RATE: u32 = 16000;
+pub const CHANNELS: u16 = 1;
+
+use crate::types::languages::LanguageHint;
+use serde::Deserialize;
+use std::ops::Range;
 use std::time::{Duration, Instant};
 
+/// Placeholder shown on the interim line until the first tokens arrive.
+pub(crate) const WAITING_TEXT: &str = "... waiting for the sound ...";
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct AudioSubtitle {
     pub(crate) speaker: Option<String>,
+    /// Language Soniox detected for this line, if language identification is enabled.
+    pub(crate) language: Option<LanguageHint>,
     pub(crate) text: String, // Keep for backward compatibility or as "target"
+    /// The text as Soniox sent it, while a word filter has masked `text` (finalized blocks only).
+    pub(crate) unmasked_text: Option<String>,
+    /// Original speech behind a translated block, shown above it with `translate_display = "both"`
+    /// or beside it with `"side_by_side"`.
+    pub(crate) source_text: Option<String>,
     pub(crate) displayed_text: String,
+    /// Byte ranges of `text` whose tokens fell below `min_confidence` (interim line only).
+    pub(crate) low_confidence: Vec<Range<usize>>,
+    /// `[hh:mm:ss]` label drawn before a finalized block with `show_timestamps`.
+    pub(crate) timestamp: Option<String>,
     pub(crate) last_update: Instant,
 }
 
-#[derive(Debug)]
+/// WASAPI sharing mode for single-device capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioCaptureMode {
+    #[default]
+    Shared,
+    /// Lower latency, but takes the device for itself. Not available for loopback.
+    Exclusive,
+}
+
+/// How samples beyond full scale are handled when audio is converted to 16-bit PCM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimiterMode {
+    /// Clamp to full scale.
+    #[default]
+    Hard,
+    /// Round peaks off with a tanh curve above half scale, which distorts loud audio less.
+    Soft,
+}
+
+/// What the `show_timestamps` label on finalized blocks counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampMode {
+    /// Audio time since the session started, continued across reconnects.
+    #[default]
+    Relative,
+    /// Local time of day when the block appeared.
+    Clock,
+}
+
+/// How subtitle text appears on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevealMode {
+    /// Char by char.
+    #[default]
+    Typewriter,
+    /// Final text appears at once; the interim line still types out.
+    Instant,
+    /// Whole words per tick.
+    Word,
+}
+
+/// When interim text gets frozen into final lines ahead of Soniox finalizing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FreezeMode {
+    /// At sentence punctuation as soon as it appears, and at word boundaries once stable.
+    #[default]
+    Punctuation,
+    /// Only at word boundaries, once the interim text has been unchanged for `stability_timeout_ms`.
+    Stability,
+}
+
+#[derive(Debug, Clone)]
 pub enum AudioMessage {
     Audio(AudioSample),
     Stop,
@@ -20,8 +97,13 @@ impl AudioSubtitle {
     pub fn new(speaker: Option<String>, text: String) -> Self {
         Self {
             speaker,
+            language: None,
             text: text.clone(),
+            unmasked_text: None,
+            source_text: None,
             displayed_text: String::new(),
+            low_confidence: Vec::new(),
+            timestamp: None,
             last_update: Instant::now(),
         }
     }
@@ -29,13 +111,24 @@ impl AudioSubtitle {
     pub fn new_complete(speaker: Option<String>, text: String) -> Self {
         Self {
             speaker,
+            language: None,
             text: text.clone(),
+            unmasked_text: None,
+            source_text: None,
             displayed_text: text,
+            low_confidence: Vec::new(),
+            timestamp: None,
             last_update: Instant::now(),
         }
     }
 
-    pub fn update_animation(&mut self, ignore_timer: bool) -> bool {
+    /// Reveals the next char (or word) once `interval` has passed. A zero interval or
+    /// `RevealMode::Instant` shows the whole text at once.
+    pub fn update_animation(&mut self, ignore_timer: bool, interval: Duration, reveal: RevealMode) -> bool {
+        if (interval.is_zero() || reveal == RevealMode::Instant) && self.displayed_text != self.text {
+            self.displayed_text = self.text.clone();
+            return true;
+        }
         if self.displayed_text.len() >= self.text.len() {
             // handle deletion/correction
              if self.displayed_text.len() > self.text.len() {
@@ -45,14 +138,25 @@ impl AudioSubtitle {
             return false;
         }
 
-        // Speed: 20ms per char
-        if ignore_timer || self.last_update.elapsed() > Duration::from_millis(20) {
+        if ignore_timer || self.last_update.elapsed() > interval {
             let next_char_index = self.displayed_text.chars().count();
-            if let Some(c) = self.text.chars().nth(next_char_index) {
-                self.displayed_text.push(c);
-                self.last_update = Instant::now();
-                return true;
+            let mut rest = self.text.chars().skip(next_char_index).peekable();
+            let Some(c) = rest.next() else { return false };
+            self.displayed_text.push(c);
+            if reveal == RevealMode::Word {
+                // Finish the current word: leading whitespace, then everything up to the next whitespace.
+                let mut in_word = !c.is_whitespace();
+                while let Some(&next) = rest.peek() {
+                    if next.is_whitespace() && in_word {
+                        break;
+                    }
+                    in_word |= !next.is_whitespace();
+                    self.displayed_text.push(next);
+                    rest.next();
+                }
             }
+            self.last_update = Instant::now();
+            return true;
         }
         false
     }
@@ -60,11 +164,16 @@ impl AudioSubtitle {
 
 impl Default for AudioSubtitle {
     fn default() -> Self {
-        let text = "... waiting for the sound ...".to_string();
+        let text = WAITING_TEXT.to_string();
         Self {
             speaker: None,
+            language: None,
             text: text.clone(),
+            unmasked_text: None,
+            source_text: None,
             displayed_text: text,
+            low_confidence: Vec::new(),
+            timestamp: None,
             last_update: Instant::now(),
         }
     }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LanguageHint {
     #[serde(rename = "af")]
     Afrikaans,
@@ -123,3 +123,211 @@ pub enum LanguageHint {
     #[serde(rename = "cy")]
     Welsh,
 }
+
+impl LanguageHint {
+    /// Every supported language, in declaration (alphabetical) order.
+    pub const ALL: [LanguageHint; 60] = [
+        LanguageHint::Afrikaans, LanguageHint::Albanian, LanguageHint::Arabic,
+        LanguageHint::Azerbaijani, LanguageHint::Basque, LanguageHint::Belarusian,
+        LanguageHint::Bengali, LanguageHint::Bosnian, LanguageHint::Bulgarian,
+        LanguageHint::Catalan, LanguageHint::Chinese, LanguageHint::Croatian, LanguageHint::Czech,
+        LanguageHint::Danish, LanguageHint::Dutch, LanguageHint::English, LanguageHint::Estonian,
+        LanguageHint::Finnish, LanguageHint::French, LanguageHint::Galician, LanguageHint::German,
+        LanguageHint::Greek, LanguageHint::Gujarati, LanguageHint::Hebrew, LanguageHint::Hindi,
+        LanguageHint::Hungarian, LanguageHint::Indonesian, LanguageHint::Italian,
+        LanguageHint::Japanese, LanguageHint::Kannada, LanguageHint::Kazakh, LanguageHint::Korean,
+        LanguageHint::Latvian, LanguageHint::Lithuanian, LanguageHint::Macedonian,
+        LanguageHint::Malay, LanguageHint::Malayalam, LanguageHint::Marathi,
+        LanguageHint::Norwegian, LanguageHint::Persian, LanguageHint::Polish,
+        LanguageHint::Portuguese, LanguageHint::Punjabi, LanguageHint::Romanian,
+        LanguageHint::Russian, LanguageHint::Serbian, LanguageHint::Slovak, LanguageHint::Slovenian,
+        LanguageHint::Spanish, LanguageHint::Swahili, LanguageHint::Swedish, LanguageHint::Tagalog,
+        LanguageHint::Tamil, LanguageHint::Telugu, LanguageHint::Thai, LanguageHint::Turkish,
+        LanguageHint::Ukrainian, LanguageHint::Urdu, LanguageHint::Vietnamese, LanguageHint::Welsh,
+    ];
+
+    /// English name, for labels.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LanguageHint::Afrikaans => "Afrikaans",
+            LanguageHint::Albanian => "Albanian",
+            LanguageHint::Arabic => "Arabic",
+            LanguageHint::Azerbaijani => "Azerbaijani",
+            LanguageHint::Basque => "Basque",
+            LanguageHint::Belarusian => "Belarusian",
+            LanguageHint::Bengali => "Bengali",
+            LanguageHint::Bosnian => "Bosnian",
+            LanguageHint::Bulgarian => "Bulgarian",
+            LanguageHint::Catalan => "Catalan",
+            LanguageHint::Chinese => "Chinese",
+            LanguageHint::Croatian => "Croatian",
+            LanguageHint::Czech => "Czech",
+            LanguageHint::Danish => "Danish",
+            LanguageHint::Dutch => "Dutch",
+            LanguageHint::English => "English",
+            LanguageHint::Estonian => "Estonian",
+            LanguageHint::Finnish => "Finnish",
+            LanguageHint::French => "French",
+            LanguageHint::Galician => "Galician",
+            LanguageHint::German => "German",
+            LanguageHint::Greek => "Greek",
+            LanguageHint::Gujarati => "Gujarati",
+            LanguageHint::Hebrew => "Hebrew",
+            LanguageHint::Hindi => "Hindi",
+            LanguageHint::Hungarian => "Hungarian",
+            LanguageHint::Indonesian => "Indonesian",
+            LanguageHint::Italian => "Italian",
+            LanguageHint::Japanese => "Japanese",
+            LanguageHint::Kannada => "Kannada",
+            LanguageHint::Kazakh => "Kazakh",
+            LanguageHint::Korean => "Korean",
+            LanguageHint::Latvian => "Latvian",
+            LanguageHint::Lithuanian => "Lithuanian",
+            LanguageHint::Macedonian => "Macedonian",
+            LanguageHint::Malay => "Malay",
+            LanguageHint::Malayalam => "Malayalam",
+            LanguageHint::Marathi => "Marathi",
+            LanguageHint::Norwegian => "Norwegian",
+            LanguageHint::Persian => "Persian",
+            LanguageHint::Polish => "Polish",
+            LanguageHint::Portuguese => "Portuguese",
+            LanguageHint::Punjabi => "Punjabi",
+            LanguageHint::Romanian => "Romanian",
+            LanguageHint::Russian => "Russian",
+            LanguageHint::Serbian => "Serbian",
+            LanguageHint::Slovak => "Slovak",
+            LanguageHint::Slovenian => "Slovenian",
+            LanguageHint::Spanish => "Spanish",
+            LanguageHint::Swahili => "Swahili",
+            LanguageHint::Swedish => "Swedish",
+            LanguageHint::Tagalog => "Tagalog",
+            LanguageHint::Tamil => "Tamil",
+            LanguageHint::Telugu => "Telugu",
+            LanguageHint::Thai => "Thai",
+            LanguageHint::Turkish => "Turkish",
+            LanguageHint::Ukrainian => "Ukrainian",
+            LanguageHint::Urdu => "Urdu",
+            LanguageHint::Vietnamese => "Vietnamese",
+            LanguageHint::Welsh => "Welsh",
+        }
+    }
+
+    /// Written right to left.
+    pub fn is_rtl(&self) -> bool {
+        matches!(self, LanguageHint::Arabic | LanguageHint::Hebrew | LanguageHint::Persian | LanguageHint::Urdu)
+    }
+
+    /// ISO 639-1 code, as used by the Soniox API.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LanguageHint::Afrikaans => "af",
+            LanguageHint::Albanian => "sq",
+            LanguageHint::Arabic => "ar",
+            LanguageHint::Azerbaijani => "az",
+            LanguageHint::Basque => "eu",
+            LanguageHint::Belarusian => "be",
+            LanguageHint::Bengali => "bn",
+            LanguageHint::Bosnian => "bs",
+            LanguageHint::Bulgarian => "bg",
+            LanguageHint::Catalan => "ca",
+            LanguageHint::Chinese => "zh",
+            LanguageHint::Croatian => "hr",
+            LanguageHint::Czech => "cs",
+            LanguageHint::Danish => "da",
+            LanguageHint::Dutch => "nl",
+            LanguageHint::English => "en",
+            LanguageHint::Estonian => "et",
+            LanguageHint::Finnish => "fi",
+            LanguageHint::French => "fr",
+            LanguageHint::Galician => "gl",
+            LanguageHint::German => "de",
+            LanguageHint::Greek => "el",
+            LanguageHint::Gujarati => "gu",
+            LanguageHint::Hebrew => "he",
+            LanguageHint::Hindi => "hi",
+            LanguageHint::Hungarian => "hu",
+            LanguageHint::Indonesian => "id",
+            LanguageHint::Italian => "it",
+            LanguageHint::Japanese => "ja",
+            LanguageHint::Kannada => "kn",
+            LanguageHint::Kazakh => "kk",
+            LanguageHint::Korean => "ko",
+            LanguageHint::Latvian => "lv",
+            LanguageHint::Lithuanian => "lt",
+            LanguageHint::Macedonian => "mk",
+            LanguageHint::Malay => "ms",
+            LanguageHint::Malayalam => "ml",
+            LanguageHint::Marathi => "mr",
+            LanguageHint::Norwegian => "no",
+            LanguageHint::Persian => "fa",
+            LanguageHint::Polish => "pl",
+            LanguageHint::Portuguese => "pt",
+            LanguageHint::Punjabi => "pa",
+            LanguageHint::Romanian => "ro",
+            LanguageHint::Russian => "ru",
+            LanguageHint::Serbian => "sr",
+            LanguageHint::Slovak => "sk",
+            LanguageHint::Slovenian => "sl",
+            LanguageHint::Spanish => "es",
+            LanguageHint::Swahili => "sw",
+            LanguageHint::Swedish => "sv",
+            LanguageHint::Tagalog => "tl",
+            LanguageHint::Tamil => "ta",
+            LanguageHint::Telugu => "te",
+            LanguageHint::Thai => "th",
+            LanguageHint::Turkish => "tr",
+            LanguageHint::Ukrainian => "uk",
+            LanguageHint::Urdu => "ur",
+            LanguageHint::Vietnamese => "vi",
+            LanguageHint::Welsh => "cy",
+        }
+    }
+}
+
+impl std::fmt::Display for LanguageHint {
+    /// The English name; use `code()` for the API form.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for LanguageHint {
+    type Err = String;
+
+    /// Accepts the ISO code (`"hu"`) or the English name (`"Hungarian"`), case-insensitively.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        LanguageHint::ALL
+            .into_iter()
+            .find(|hint| hint.code().eq_ignore_ascii_case(s) || hint.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown language `{}`", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_language_round_trips_through_code_and_name() {
+        for hint in LanguageHint::ALL {
+            assert_eq!(hint.code().parse::<LanguageHint>(), Ok(hint));
+            assert_eq!(hint.code().to_uppercase().parse::<LanguageHint>(), Ok(hint));
+            assert_eq!(hint.to_string(), hint.name());
+            assert_eq!(hint.to_string().parse::<LanguageHint>(), Ok(hint));
+            // The serde rename is the code, so config files and the API agree with code().
+            let json = format!("\"{}\"", hint.code());
+            assert_eq!(serde_json::from_str::<LanguageHint>(&json).ok(), Some(hint));
+        }
+    }
+
+    #[test]
+    fn codes_and_names_are_unique() {
+        let codes: HashSet<_> = LanguageHint::ALL.iter().map(|hint| hint.code()).collect();
+        let names: HashSet<_> = LanguageHint::ALL.iter().map(|hint| hint.name()).collect();
+        assert_eq!(codes.len(), LanguageHint::ALL.len());
+        assert_eq!(names.len(), LanguageHint::ALL.len());
+        assert!("xx".parse::<LanguageHint>().is_err());
+    }
+}
@@ -1,38 +1,213 @@
 use crate::errors::SonioxWindowsErrors;
+use crate::soniox::export::{ExportFormat, TranscriptFormat};
+use crate::types::audio::{AudioCaptureMode, FreezeMode, LimiterMode, RevealMode, TimestampMode};
 use crate::types::languages::LanguageHint;
+use crate::types::soniox::{TranslateDisplay, TranslationType};
+use crate::windows::utils::{Hotkey, MonitorRect, parse_hotkey};
 use config::{Config, ConfigError, File};
+use eframe::egui::Align2;
 use log::LevelFilter;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
 use std::str::FromStr;
 
+const MAX_GAIN: f32 = 4.0;
+const STATUS_CORNERS: [&str; 4] = ["top_left", "top_right", "bottom_left", "bottom_right"];
+/// Each target language is a Soniox session of its own, so the list is kept short.
+const MAX_TARGET_LANGUAGES: usize = 4;
+/// Longer `context_file` contents are cut to this size before being sent to Soniox.
+pub const MAX_CONTEXT_BYTES: usize = 10_000;
+/// `smart_delay_ms` is capped here; longer delays make captions feel detached from speech.
+const MAX_SMART_DELAY_MS: u64 = 2_000;
+pub const CONFIG_PATH: &str = "config.toml";
+/// Used when `api_key` is missing or empty in the config file.
+pub const API_KEY_ENV: &str = "SONIOX_API_KEY";
+
+/// Horizontal placement of subtitle lines inside the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Reading direction of subtitle lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextDirection {
+    /// Per line, from the detected language or the first Hebrew/Arabic-script letter.
+    #[default]
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+/// Which way the subtitle stack grows as new lines arrive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlowDirection {
+    /// Anchored at the bottom; older lines move up (captions).
+    Up,
+    /// Anchored at the top; new lines appear below older ones (teleprompter).
+    Down,
+}
+
+/// Where the overlay sits among other windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZOrder {
+    /// Above every normal window.
+    #[default]
+    Topmost,
+    /// Like any other window; it goes behind whatever is activated.
+    Normal,
+    /// Behind all other windows, e.g. on the desktop under windowed video.
+    Bottom,
+}
+
+/// Edge of the overlay the control strip (with `allow_interaction`) runs along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlStrip {
+    #[default]
+    Top,
+    Bottom,
+}
+
+/// When the connection status dot is drawn on the overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusIndicator {
+    /// Always; green while connected and receiving audio.
+    Always,
+    /// Only while there is no audio or no connection.
+    #[default]
+    Problems,
+    Never,
+}
+
+/// `word_filter` is either an inline list of terms or the path of a newline-delimited file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum WordFilterSetting {
+    Inline(Vec<String>),
+    File(String),
+}
+
 #[derive(Deserialize)]
 pub struct SettingsApp {
     pub(crate) language_hints: Option<Vec<LanguageHint>>,
     pub(crate) context: Option<String>,
+    pub(crate) context_file: Option<String>,
+    /// Contents of `context_file` (capped), read once at load time.
+    #[serde(skip)]
+    context_from_file: Option<String>,
+    /// Size of `context_file` before capping, for logging.
+    #[serde(skip)]
+    context_file_bytes: usize,
     pub(crate) api_key: Option<String>,
     pub(crate) target_language: Option<LanguageHint>,
+    pub(crate) target_languages: Option<Vec<LanguageHint>>,
     pub(crate) enable_translate: Option<bool>,
+    pub(crate) translation_type: Option<TranslationType>,
+    pub(crate) translate_display: Option<TranslateDisplay>,
+    pub(crate) language_a: Option<LanguageHint>,
+    pub(crate) language_b: Option<LanguageHint>,
     enable_high_priority: Option<bool>,
+    pub(crate) z_order: Option<ZOrder>,
     enable_speakers: Option<bool>,
+    enable_speaker_blocks: Option<bool>,
+    detect_language: Option<bool>,
+    endpoint_detection: Option<bool>,
     model: Option<String>,
     level: Option<String>,
     pub(crate) font_size: Option<f32>,
+    pub(crate) font_size_overrides: Option<HashMap<LanguageHint, f32>>,
+    pub(crate) font_path: Option<String>,
+    pub(crate) fallback_fonts: Option<Vec<String>>,
     pub(crate) text_color: Option<(u8, u8, u8)>,
+    pub(crate) show_text_background: Option<bool>,
+    pub(crate) text_align: Option<TextAlign>,
+    pub(crate) text_direction: Option<TextDirection>,
+    pub(crate) fade_old_lines: Option<bool>,
+    pub(crate) show_speaker_labels: Option<bool>,
+    pub(crate) show_timestamps: Option<bool>,
+    pub(crate) timestamp_mode: Option<TimestampMode>,
+    pub(crate) flow_direction: Option<FlowDirection>,
+    pub(crate) background_color: Option<(u8, u8, u8, u8)>,
     pub(crate) window_width: Option<f32>,
+    pub(crate) wrap_width_ratio: Option<f32>,
     pub(crate) window_height: Option<f32>,
     pub(crate) window_anchor: Option<String>,
     pub(crate) window_offset: Option<(f32, f32)>,
+    pub(crate) monitor_index: Option<usize>,
+    pub(crate) enable_drag_move: Option<bool>,
+    pub(crate) lock_window_size: Option<bool>,
+    pub(crate) allow_interaction: Option<bool>,
+    pub(crate) control_strip: Option<ControlStrip>,
+    pub(crate) toggle_hotkey: Option<String>,
+    pub(crate) clear_hotkey: Option<String>,
+    pub(crate) font_larger_hotkey: Option<String>,
+    pub(crate) font_smaller_hotkey: Option<String>,
+    pub(crate) debug_hotkey: Option<String>,
+    pub(crate) commit_hotkey: Option<String>,
+    pub(crate) pause_stops_audio: Option<bool>,
     pub(crate) audio_input: Option<String>,
+    pub(crate) mic_gain: Option<f32>,
+    pub(crate) system_gain: Option<f32>,
+    pub(crate) max_system_buffer_ms: Option<u64>,
+    pub(crate) capture_mode: Option<AudioCaptureMode>,
+    pub(crate) noise_gate_threshold: Option<f32>,
+    pub(crate) auto_gain: Option<bool>,
+    pub(crate) limiter: Option<LimiterMode>,
     pub(crate) show_window_border: Option<bool>,
     pub(crate) debug_window: Option<bool>,
+    pub(crate) debug_window_size: Option<(f32, f32)>,
+    pub(crate) debug_window_pos: Option<(f32, f32)>,
+    pub(crate) show_status_indicator: Option<StatusIndicator>,
+    pub(crate) status_indicator_corner: Option<String>,
 
     pub(crate) show_interim: Option<bool>,
+    pub(crate) max_lines: Option<usize>,
     pub(crate) stability_timeout_ms: Option<u64>,
+    pub(crate) smart_delay_ms: Option<u64>,
+    pub(crate) freeze_mode: Option<FreezeMode>,
+    pub(crate) clear_after_silence_ms: Option<u64>,
+    pub(crate) min_line_display_ms: Option<u64>,
+    pub(crate) animation_speed_ms: Option<u64>,
+    pub(crate) reveal_mode: Option<RevealMode>,
+    pub(crate) min_confidence: Option<f64>,
+    pub(crate) drop_confidence: Option<f64>,
+    pub(crate) max_reconnects: Option<u32>,
+    pub(crate) keepalive_interval_secs: Option<u64>,
+    pub(crate) audio_chunk_ms: Option<u64>,
+    pub(crate) empty_audio_timeout_ms: Option<u64>,
     pub(crate) enable_raw_logging: Option<bool>,
     pub(crate) enable_audio_logging: Option<bool>,
 
     pub(crate) save_transcription: Option<bool>,
     pub(crate) transcript_save_path: Option<String>,
+    pub(crate) word_filter: Option<WordFilterSetting>,
+    pub(crate) export_format: Option<ExportFormat>,
+    pub(crate) transcript_format: Option<TranscriptFormat>,
+    pub(crate) caption_server_port: Option<u16>,
+    pub(crate) obs_text_file: Option<String>,
+    pub(crate) notes: Option<String>,
+
+    pub(crate) session_id: Option<String>,
+    /// Used as the session id when `session_id` isn't configured.
+    #[serde(skip)]
+    generated_session_id: String,
+    /// File these settings were loaded from, so runtime changes can be written back.
+    #[serde(skip)]
+    config_path: String,
+    /// Where the API key came from, for the log. Never the key itself.
+    #[serde(skip)]
+    api_key_source: &'static str,
 }
 
 impl SettingsApp {
@@ -40,103 +215,366 @@ impl SettingsApp {
         let s = Config::builder()
             .add_source(File::with_name(path))
             .build()?;
-        s.try_deserialize()
+        let mut settings: Self = s.try_deserialize()?;
+        settings.generated_session_id = generate_session_id();
+        settings.config_path = path.to_string();
+        settings.api_key_source = "config file";
+        if settings.api_key.as_deref().is_none_or(|key| key.trim().is_empty()) {
+            if let Ok(key) = std::env::var(API_KEY_ENV) {
+                settings.api_key = Some(key);
+                settings.api_key_source = API_KEY_ENV;
+            }
+        }
+        if let Some(path) = &settings.context_file {
+            let mut contents = std::fs::read_to_string(path)
+                .map_err(|e| ConfigError::Message(format!("Failed to read context_file '{}': {}", path, e)))?;
+            settings.context_file_bytes = contents.len();
+            if contents.len() > MAX_CONTEXT_BYTES {
+                let mut cut = MAX_CONTEXT_BYTES;
+                while !contents.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                contents.truncate(cut);
+            }
+            settings.context_from_file = Some(contents);
+        }
+        Ok(settings)
     }
 
     pub fn validate(&self) -> Result<(), String> {
+        // Everything else has a default, so configs written for older versions keep working.
         let mut missing_fields = Vec::new();
-        if self.language_hints.is_none() { missing_fields.push("language_hints"); }
-        if self.context.is_none() { missing_fields.push("context"); }
-        if self.api_key.is_none() { missing_fields.push("api_key"); }
-        // target_language is optional if enable_translate is false, but let's stick to the list for now or keep it rigid?
-        // The previous code had it mandatory. Let's keep it mandatory as per previous struct.
-        if self.target_language.is_none() { missing_fields.push("target_language"); }
-        if self.enable_translate.is_none() { missing_fields.push("enable_translate"); }
-        if self.enable_high_priority.is_none() { missing_fields.push("enable_high_priority"); }
-        if self.enable_speakers.is_none() { missing_fields.push("enable_speakers"); }
-        if self.model.is_none() { missing_fields.push("model"); }
-        if self.level.is_none() { missing_fields.push("level"); }
-        if self.font_size.is_none() { missing_fields.push("font_size"); }
-        if self.text_color.is_none() { missing_fields.push("text_color"); }
-        if self.window_width.is_none() { missing_fields.push("window_width"); }
-        if self.window_height.is_none() { missing_fields.push("window_height"); }
-        if self.window_anchor.is_none() { missing_fields.push("window_anchor"); }
-        if self.window_offset.is_none() { missing_fields.push("window_offset"); }
-        if self.audio_input.is_none() { missing_fields.push("audio_input"); }
-        if self.show_window_border.is_none() { missing_fields.push("show_window_border"); }
-        if self.debug_window.is_none() { missing_fields.push("debug_window"); }
-
-        if self.show_interim.is_none() { missing_fields.push("show_interim"); }
-        if self.stability_timeout_ms.is_none() { missing_fields.push("stability_timeout_ms"); }
-        if self.enable_raw_logging.is_none() { missing_fields.push("enable_raw_logging"); }
-        if self.enable_audio_logging.is_none() { missing_fields.push("enable_audio_logging"); }
-        
-        if self.save_transcription.is_none() { missing_fields.push("save_transcription"); }
-        // transcript_save_path is optional, defaults to "transcript.txt" if missing/but logging enabled?
-        // Actually, let's make it mandatory if logging is enabled, or just mandatory with a default suggestion in example.
-        // User rules say "All configuration parameters in config.toml must be mandatory."
-        // So we strictly enforce it.
-        if self.transcript_save_path.is_none() { missing_fields.push("transcript_save_path"); }
-
+        if self.api_key.as_deref().is_none_or(|key| key.trim().is_empty()) { missing_fields.push("api_key"); }
+        if self.language_hints.as_ref().is_none_or(|hints| hints.is_empty()) { missing_fields.push("language_hints"); }
+        let has_target = self.target_language.is_some() || self.target_languages.as_ref().is_some_and(|targets| !targets.is_empty());
+        if self.enable_translate() && !has_target && self.translation_type() == TranslationType::OneWay {
+            missing_fields.push("target_language");
+        }
 
         if !missing_fields.is_empty() {
              return Err(format!("Missing mandatory fields in config.toml: {}", missing_fields.join(", ")));
         }
+
+        if self.translation_type == Some(TranslationType::TwoWay) {
+            match (self.language_a, self.language_b) {
+                (Some(a), Some(b)) if a != b => {}
+                (Some(_), Some(_)) => {
+                    return Err("`language_a` and `language_b` must be different for two_way translation".to_string());
+                }
+                _ => {
+                    return Err("`translation_type = \"two_way\"` requires both `language_a` and `language_b`".to_string());
+                }
+            }
+        }
+
+        if let Some(targets) = &self.target_languages {
+            if self.translation_type() == TranslationType::TwoWay {
+                return Err("`target_languages` only applies to one_way translation".to_string());
+            }
+            if targets.len() > MAX_TARGET_LANGUAGES {
+                return Err(format!("`target_languages` can list at most {} languages (got {})", MAX_TARGET_LANGUAGES, targets.len()));
+            }
+            if let Some(duplicate) = targets.iter().enumerate().find_map(|(i, target)| targets[..i].contains(target).then_some(target)) {
+                return Err(format!("`target_languages` lists \"{}\" more than once", duplicate.code()));
+            }
+        }
+
+        if let Some((language, size)) = self.font_size_overrides.iter().flatten().find(|(_, size)| **size <= 0.0) {
+            return Err(format!("`font_size_overrides.{}` must be greater than 0 (got {})", language.code(), size));
+        }
+
+        for (name, gain) in [("mic_gain", self.mic_gain), ("system_gain", self.system_gain)] {
+            if let Some(gain) = gain {
+                if !(0.0..=MAX_GAIN).contains(&gain) {
+                    return Err(format!("`{}` must be between 0.0 and {:.1} (got {})", name, MAX_GAIN, gain));
+                }
+            }
+        }
+        if let Some(threshold) = self.noise_gate_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(format!("`noise_gate_threshold` must be between 0.0 and 1.0 (got {})", threshold));
+            }
+        }
+        for (name, confidence) in [("min_confidence", self.min_confidence), ("drop_confidence", self.drop_confidence)] {
+            if let Some(confidence) = confidence {
+                if !(0.0..=1.0).contains(&confidence) {
+                    return Err(format!("`{}` must be between 0.0 and 1.0 (got {})", name, confidence));
+                }
+            }
+        }
+        if let (Some(min), Some(drop)) = (self.min_confidence, self.drop_confidence) {
+            if drop > min && min > 0.0 {
+                return Err(format!("`drop_confidence` ({}) must not be above `min_confidence` ({})", drop, min));
+            }
+        }
+        if let Some(ratio) = self.wrap_width_ratio {
+            if !(0.1..=1.0).contains(&ratio) {
+                return Err(format!("`wrap_width_ratio` must be between 0.1 and 1.0 (got {})", ratio));
+            }
+        }
+        if self.max_lines == Some(0) {
+            return Err("`max_lines` must be greater than 0".to_string());
+        }
+        if self.max_system_buffer_ms == Some(0) {
+            return Err("`max_system_buffer_ms` must be greater than 0".to_string());
+        }
+        if let Some((width, height)) = self.debug_window_size {
+            if width <= 0.0 || height <= 0.0 {
+                return Err(format!("`debug_window_size` must be positive (got [{}, {}])", width, height));
+            }
+        }
+        if let Some(corner) = self.status_indicator_corner.as_deref() {
+            if !STATUS_CORNERS.contains(&corner) {
+                return Err(format!(
+                    "`status_indicator_corner` must be one of {} (got \"{}\")",
+                    STATUS_CORNERS.join(", "),
+                    corner
+                ));
+            }
+        }
+        let hotkeys = [
+            ("toggle_hotkey", &self.toggle_hotkey),
+            ("clear_hotkey", &self.clear_hotkey),
+            ("font_larger_hotkey", &self.font_larger_hotkey),
+            ("font_smaller_hotkey", &self.font_smaller_hotkey),
+            ("debug_hotkey", &self.debug_hotkey),
+            ("commit_hotkey", &self.commit_hotkey),
+        ];
+        for (name, combo) in hotkeys {
+            if let Some(combo) = combo.as_deref().filter(|c| !c.is_empty()) {
+                parse_hotkey(combo).map_err(|e| format!("`{}`: {}", name, e))?;
+            }
+        }
         Ok(())
     }
 
+    /// Settings that differ in `newer` but only take effect on the next launch
+    /// (the stream, audio capture, window creation and hotkey registration read them once).
+    pub fn restart_required_changes(&self, newer: &SettingsApp) -> Vec<&'static str> {
+        let checks = [
+            ("api_key", self.api_key() != newer.api_key()),
+            ("model", self.model() != newer.model()),
+            ("language_hints", self.language_hints() != newer.language_hints()),
+            ("context", self.context() != newer.context()),
+            ("enable_translate", self.enable_translate() != newer.enable_translate()),
+            ("target_language(s)", self.target_languages() != newer.target_languages()),
+            ("translation_type", self.translation_type() != newer.translation_type()),
+            ("translate_display", self.translate_display() != newer.translate_display()),
+            ("language_a/language_b", (self.language_a(), self.language_b()) != (newer.language_a(), newer.language_b())),
+            ("enable_speakers", self.enable_speakers() != newer.enable_speakers()),
+            ("detect_language", self.detect_language() != newer.detect_language()),
+            ("endpoint_detection", self.endpoint_detection() != newer.endpoint_detection()),
+            ("session_id", self.session_id != newer.session_id),
+            ("audio_input", self.audio_input() != newer.audio_input()),
+            ("mic_gain/system_gain", (self.mic_gain(), self.system_gain()) != (newer.mic_gain(), newer.system_gain())),
+            ("capture_mode", self.capture_mode() != newer.capture_mode()),
+            ("noise_gate_threshold", self.noise_gate_threshold() != newer.noise_gate_threshold()),
+            ("auto_gain", self.auto_gain() != newer.auto_gain()),
+            ("limiter", self.limiter() != newer.limiter()),
+            // An unlocked window is resized live instead.
+            (
+                "window_width/window_height",
+                self.lock_window_size() && (self.window_width(), self.window_height()) != (newer.window_width(), newer.window_height()),
+            ),
+            ("lock_window_size", self.lock_window_size() != newer.lock_window_size()),
+            ("z_order", self.z_order() != newer.z_order()),
+            ("window_anchor", self.window_anchor != newer.window_anchor),
+            ("monitor_index", self.monitor_index() != newer.monitor_index()),
+            ("font_path/fallback_fonts", (self.font_path(), self.fallback_fonts()) != (newer.font_path(), newer.fallback_fonts())),
+            ("max_lines", self.max_lines() != newer.max_lines()),
+            ("save_transcription", self.save_transcription() != newer.save_transcription()),
+            ("transcript_save_path", self.transcript_save_path() != newer.transcript_save_path()),
+            ("export_format", self.export_format() != newer.export_format()),
+            ("caption_server_port", self.caption_server_port() != newer.caption_server_port()),
+            ("obs_text_file", self.obs_text_file() != newer.obs_text_file()),
+            ("level", self.level != newer.level),
+        ];
+        checks.into_iter().filter(|(_, changed)| *changed).map(|(name, _)| name).collect()
+    }
+
     pub fn language_hints(&self) -> &[LanguageHint] {
         self.language_hints.as_ref().expect("Validated")
     }
 
+    /// The `context_file` contents if one is configured, otherwise the inline `context`.
     pub fn context(&self) -> &str {
-        self.context.as_ref().expect("Validated")
+        self.context_from_file
+            .as_deref()
+            .unwrap_or_else(|| self.context.as_deref().unwrap_or(""))
+    }
+
+    /// Configured `context_file` and its size in bytes before capping.
+    pub fn context_file(&self) -> Option<(&str, usize)> {
+        self.context_file.as_deref().map(|path| (path, self.context_file_bytes))
+    }
+
+    /// `"config file"` or the environment variable the key was read from.
+    pub fn api_key_source(&self) -> &'static str {
+        self.api_key_source
     }
 
     pub fn api_key(&self) -> &str {
         self.api_key.as_ref().expect("Validated")
     }
 
+    /// The first (or only) one-way target. Only required for one-way translation. Defaults to English.
     pub fn target_language(&self) -> LanguageHint {
-         self.target_language.clone().expect("Validated")
+        self.target_languages()[0]
     }
 
+    /// Every one-way target: `target_languages` if set, replacing `target_language`. Never empty.
+    pub fn target_languages(&self) -> Vec<LanguageHint> {
+        match &self.target_languages {
+            Some(targets) if !targets.is_empty() => targets.clone(),
+            _ => vec![self.target_language.unwrap_or(LanguageHint::English)],
+        }
+    }
+
+    /// Targets after the first, each shown in a lane of its own; empty unless translating one-way.
+    pub fn extra_target_languages(&self) -> Vec<LanguageHint> {
+        if !self.enable_translate() || self.translation_type() != TranslationType::OneWay {
+            return Vec::new();
+        }
+        self.target_languages().split_off(1)
+    }
+
+    /// Defaults to false.
     pub fn enable_speakers(&self) -> bool {
-        self.enable_speakers.expect("Validated")
+        self.enable_speakers.unwrap_or(false)
+    }
+
+    /// Give each speaker their own block instead of merging into one run. Defaults to false.
+    pub fn enable_speaker_blocks(&self) -> bool {
+        self.enable_speaker_blocks.unwrap_or(false)
+    }
+
+    /// Defaults to false.
+    pub fn detect_language(&self) -> bool {
+        self.detect_language.unwrap_or(false)
     }
 
+    /// Whether Soniox should finalize text at detected pauses. Defaults to true.
+    pub fn endpoint_detection(&self) -> bool {
+        self.endpoint_detection.unwrap_or(true)
+    }
+
+    /// Defaults to `stt-rt-v3`.
     pub fn model(&self) -> &str {
-        self.model.as_ref().expect("Validated")
+        self.model.as_deref().unwrap_or("stt-rt-v3")
     }
 
+    /// Defaults to false.
     pub fn enable_translate(&self) -> bool {
-        self.enable_translate.expect("Validated")
+        self.enable_translate.unwrap_or(false)
+    }
+
+    /// Defaults to one-way.
+    pub fn translation_type(&self) -> TranslationType {
+        self.translation_type.unwrap_or(TranslationType::OneWay)
+    }
+
+    /// Defaults to translation only.
+    pub fn translate_display(&self) -> TranslateDisplay {
+        self.translate_display.unwrap_or_default()
+    }
+
+    /// Whether the overlay draws the original speech and its translation in two columns.
+    pub fn side_by_side(&self) -> bool {
+        self.enable_translate() && self.translate_display() == TranslateDisplay::SideBySide
+    }
+
+    pub fn language_a(&self) -> Option<LanguageHint> {
+        self.language_a
     }
 
+    pub fn language_b(&self) -> Option<LanguageHint> {
+        self.language_b
+    }
+
+    /// Defaults to true.
     pub fn enable_high_priority(&self) -> bool {
-        self.enable_high_priority.expect("Validated")
+        self.enable_high_priority.unwrap_or(true)
+    }
+
+    /// Defaults to topmost.
+    pub fn z_order(&self) -> ZOrder {
+        self.z_order.unwrap_or_default()
     }
 
+    /// Whether the debug window is open at startup. Defaults to false.
     pub fn debug_window(&self) -> bool {
-        self.debug_window.expect("Validated")
+        self.debug_window.unwrap_or(false)
     }
 
 
 
+    /// Defaults to false.
     pub fn show_interim(&self) -> bool {
-        self.show_interim.expect("Validated")
+        self.show_interim.unwrap_or(false)
+    }
+
+    /// When interim text is frozen into final lines. Defaults to punctuation.
+    pub fn freeze_mode(&self) -> FreezeMode {
+        self.freeze_mode.unwrap_or_default()
+    }
+
+    /// Hold-back before responses are shown, capped at MAX_SMART_DELAY_MS. Defaults to 0.
+    pub fn smart_delay_ms(&self) -> u64 {
+        self.smart_delay_ms.unwrap_or(0).min(MAX_SMART_DELAY_MS)
+    }
+
+    /// How long each new block stays the newest before more final text is shown. Defaults to 0 (no minimum).
+    pub fn min_line_display_ms(&self) -> u64 {
+        self.min_line_display_ms.unwrap_or(0)
+    }
+
+    /// Captions fade out and clear after this long without new tokens. Defaults to 0 (never).
+    pub fn clear_after_silence_ms(&self) -> u64 {
+        self.clear_after_silence_ms.unwrap_or(0)
+    }
+
+    /// Typewriter delay per char in ms. Defaults to 20; 0 shows text instantly.
+    pub fn animation_speed_ms(&self) -> u64 {
+        self.animation_speed_ms.unwrap_or(20)
+    }
+
+    /// How text is revealed. Defaults to typewriter.
+    pub fn reveal_mode(&self) -> RevealMode {
+        self.reveal_mode.unwrap_or_default()
+    }
+
+    /// Defaults to 50.
+    pub fn max_lines(&self) -> usize {
+        self.max_lines.unwrap_or(50)
     }
 
+    /// Defaults to 0.
     pub fn stability_timeout_ms(&self) -> u64 {
-        self.stability_timeout_ms.expect("Validated")
+        self.stability_timeout_ms.unwrap_or(0)
+    }
+
+    /// Interim tokens below this confidence are drawn dimmed. 0.0 (default) disables dimming.
+    pub fn min_confidence(&self) -> f64 {
+        self.min_confidence.unwrap_or(0.0)
     }
 
+    /// Final tokens below this confidence are left out entirely. 0.0 (default) keeps everything.
+    pub fn drop_confidence(&self) -> f64 {
+        self.drop_confidence.unwrap_or(0.0)
+    }
+
+    /// Defaults to 24.
     pub fn font_size(&self) -> f32 {
-        self.font_size.expect("Validated")
+        self.font_size.unwrap_or(24.0)
+    }
+
+    /// Font size used instead of `font_size` for lines in these languages. Defaults to none.
+    pub fn font_size_overrides(&self) -> HashMap<LanguageHint, f32> {
+        self.font_size_overrides.clone().unwrap_or_default()
     }
 
     pub fn level(&self) -> Result<LevelFilter, SonioxWindowsErrors> {
-        LevelFilter::from_str(self.level.as_ref().expect("Validated")).map_err(|_| {
+        LevelFilter::from_str(self.level.as_deref().unwrap_or("info")).map_err(|_| {
             SonioxWindowsErrors::Internal(
                 "field `level` isn't valid. did u mean `info`, `debug` and `warn`?".to_string(),
             )
@@ -144,14 +582,140 @@ impl SettingsApp {
     }
 
     pub fn text_color(&self) -> eframe::egui::Color32 {
-        let (r, g, b) = self.text_color.expect("Validated");
+        let (r, g, b) = self.text_color.unwrap_or((255, 255, 0));
         eframe::egui::Color32::from_rgb(r, g, b)
     }
 
-    pub fn get_position(&self, screen_width: f32, screen_height: f32, window_width: f32, window_height: f32) -> (f32, f32) {
-        let anchor = self.window_anchor.as_deref().expect("Validated");
-        let offset = self.window_offset.expect("Validated");
-        let (offset_x, offset_y) = offset;
+    /// TTF/OTF file used instead of the embedded font, if set.
+    pub fn font_path(&self) -> Option<&str> {
+        self.font_path.as_deref()
+    }
+
+    /// Font files tried in order for glyphs the primary font lacks. Empty by default.
+    pub fn fallback_fonts(&self) -> &[String] {
+        self.fallback_fonts.as_deref().unwrap_or_default()
+    }
+
+    /// Horizontal alignment of the subtitle lines. Defaults to left.
+    pub fn text_align(&self) -> TextAlign {
+        self.text_align.unwrap_or_default()
+    }
+
+    /// Defaults to auto-detecting right-to-left lines.
+    pub fn text_direction(&self) -> TextDirection {
+        self.text_direction.unwrap_or_default()
+    }
+
+    /// Defaults to `down` for top-anchored windows and `up` otherwise.
+    pub fn flow_direction(&self) -> FlowDirection {
+        self.flow_direction.unwrap_or_else(|| {
+            let anchor = self.window_anchor.as_deref().unwrap_or("bottom_center");
+            if anchor == "top" || anchor.starts_with("top_") {
+                FlowDirection::Down
+            } else {
+                FlowDirection::Up
+            }
+        })
+    }
+
+    /// Fade older lines out towards the top of the window. Defaults to false.
+    pub fn fade_old_lines(&self) -> bool {
+        self.fade_old_lines.unwrap_or(false)
+    }
+
+    /// The "speaker >> " prefix on screen; the transcript keeps speakers either way. Defaults to true.
+    pub fn show_speaker_labels(&self) -> bool {
+        self.show_speaker_labels.unwrap_or(true)
+    }
+
+    /// Timestamp label on finalized blocks, of `timestamp_mode` (default relative), if `show_timestamps`
+    /// is on (default off).
+    pub fn timestamps(&self) -> Option<TimestampMode> {
+        self.show_timestamps
+            .unwrap_or(false)
+            .then(|| self.timestamp_mode.unwrap_or_default())
+    }
+
+    /// Panel color behind each line, if `show_text_background` is on (default off).
+    /// `background_color` is RGBA and defaults to semi-transparent black.
+    pub fn text_background(&self) -> Option<eframe::egui::Color32> {
+        if !self.show_text_background.unwrap_or(false) {
+            return None;
+        }
+        let (r, g, b, a) = self.background_color.unwrap_or((0, 0, 0, 160));
+        Some(eframe::egui::Color32::from_rgba_unmultiplied(r, g, b, a))
+    }
+
+    /// Free text about the project, shown by `--list`. The overlay never reads it.
+    pub fn notes(&self) -> Option<&str> {
+        self.notes.as_deref().map(str::trim).filter(|notes| !notes.is_empty())
+    }
+
+    pub fn config_path(&self) -> &str {
+        &self.config_path
+    }
+
+    /// Ctrl-drag moves the overlay and saves the new `window_offset`. Defaults to true.
+    pub fn enable_drag_move(&self) -> bool {
+        self.enable_drag_move.unwrap_or(true)
+    }
+
+    /// Global hotkey that hides/shows the captions. Defaults to `ctrl+alt+h`; an empty string disables it.
+    pub fn toggle_hotkey(&self) -> Option<Hotkey> {
+        hotkey_or(&self.toggle_hotkey, "ctrl+alt+h")
+    }
+
+    /// Global hotkey that wipes the on-screen lines. Defaults to `ctrl+alt+c`; an empty string disables it.
+    pub fn clear_hotkey(&self) -> Option<Hotkey> {
+        hotkey_or(&self.clear_hotkey, "ctrl+alt+c")
+    }
+
+    /// Global hotkeys that grow/shrink the font. Default to `ctrl+alt+plus` / `ctrl+alt+minus`.
+    pub fn font_size_hotkeys(&self) -> (Option<Hotkey>, Option<Hotkey>) {
+        (
+            hotkey_or(&self.font_larger_hotkey, "ctrl+alt+plus"),
+            hotkey_or(&self.font_smaller_hotkey, "ctrl+alt+minus"),
+        )
+    }
+
+    /// Global hotkey that opens/closes the debug window. Defaults to `ctrl+alt+d`; an empty string disables it.
+    pub fn debug_hotkey(&self) -> Option<Hotkey> {
+        hotkey_or(&self.debug_hotkey, "ctrl+alt+d")
+    }
+
+    /// Global hotkey that commits the interim line as a block right away. Defaults to `ctrl+alt+enter`;
+    /// an empty string disables it.
+    pub fn commit_hotkey(&self) -> Option<Hotkey> {
+        hotkey_or(&self.commit_hotkey, "ctrl+alt+enter")
+    }
+
+    /// Whether pausing also stops sending audio to Soniox. Defaults to true.
+    pub fn pause_stops_audio(&self) -> bool {
+        self.pause_stops_audio.unwrap_or(true)
+    }
+
+    /// Monitor the overlay is placed on, in EnumDisplayMonitors order. `None` means the primary monitor.
+    pub fn monitor_index(&self) -> Option<usize> {
+        self.monitor_index
+    }
+
+    /// Defaults to `[0, -100]`: just above the bottom edge.
+    pub fn window_offset(&self) -> (f32, f32) {
+        self.window_offset.unwrap_or((0.0, -100.0))
+    }
+
+    pub fn get_position(&self, monitor: &MonitorRect, window_width: f32, window_height: f32) -> (f32, f32) {
+        let (offset_x, offset_y) = self.window_offset();
+        let (x, y) = self.anchor_origin(monitor, window_width, window_height);
+        (x + offset_x, y + offset_y)
+    }
+
+    /// Window position for `window_anchor` on `monitor` before `window_offset` is applied, in points.
+    /// The window size is in points (egui scales it by the monitor's DPI), so the monitor rect is converted to match.
+    pub fn anchor_origin(&self, monitor: &MonitorRect, window_width: f32, window_height: f32) -> (f32, f32) {
+        let anchor = self.window_anchor.as_deref().unwrap_or("bottom_center");
+        let monitor = monitor.in_points();
+        let (screen_width, screen_height) = (monitor.width, monitor.height);
 
         // Refined Logic (Anchor Matching):
         // X calculation
@@ -174,38 +738,304 @@ impl SettingsApp {
              screen_height - window_height
         };
 
-        (x + offset_x, y + offset_y)
+        (monitor.x + x, monitor.y + y)
     }
 
+    /// Defaults to 800.
     pub fn window_width(&self) -> f32 {
-        self.window_width.expect("Validated")
+        self.window_width.unwrap_or(800.0)
+    }
+
+    /// Share of the window width text wraps at. Drawing and the freeze length (max_chars)
+    /// both use it, so blocks freeze where they visibly wrap. Defaults to 0.8.
+    pub fn wrap_width_ratio(&self) -> f32 {
+        self.wrap_width_ratio.unwrap_or(0.8)
     }
 
+    /// Defaults to 700.
     pub fn window_height(&self) -> f32 {
-        self.window_height.expect("Validated")
+        self.window_height.unwrap_or(700.0)
     }
 
+    /// Keeps the overlay at exactly `window_width` x `window_height`. Defaults to true.
+    pub fn lock_window_size(&self) -> bool {
+        self.lock_window_size.unwrap_or(true)
+    }
+
+    /// Keeps a strip of the overlay clickable (pause and clear buttons) while the rest stays
+    /// click-through. Defaults to false.
+    pub fn allow_interaction(&self) -> bool {
+        self.allow_interaction.unwrap_or(false)
+    }
+
+    /// Defaults to `top`.
+    pub fn control_strip(&self) -> ControlStrip {
+        self.control_strip.unwrap_or_default()
+    }
+
+    /// Defaults to `loopback` (system audio).
     pub fn audio_input(&self) -> &str {
-        self.audio_input.as_ref().expect("Validated")
+        self.audio_input.as_deref().unwrap_or("loopback")
+    }
+
+    /// Gain applied to the microphone before mixing in "both" mode. Defaults to 1.0.
+    pub fn mic_gain(&self) -> f32 {
+        self.mic_gain.unwrap_or(1.0)
+    }
+
+    /// Gain applied to the system audio before mixing in "both" mode. Defaults to 1.0.
+    pub fn system_gain(&self) -> f32 {
+        self.system_gain.unwrap_or(1.0)
+    }
+
+    /// How far (in ms) system audio may run ahead of the mic before the mixer trims it. Defaults to 200.
+    pub fn max_system_buffer_ms(&self) -> u64 {
+        self.max_system_buffer_ms.unwrap_or(200)
+    }
+
+    /// WASAPI sharing mode for single-device capture. Defaults to shared.
+    pub fn capture_mode(&self) -> AudioCaptureMode {
+        self.capture_mode.unwrap_or_default()
     }
 
+    /// RMS level below which captured audio is muted. 0.0 (default) disables the gate.
+    pub fn noise_gate_threshold(&self) -> f32 {
+        self.noise_gate_threshold.unwrap_or(0.0)
+    }
+
+    /// Automatic gain control on the outgoing audio, after the noise gate. Defaults to false.
+    pub fn auto_gain(&self) -> bool {
+        self.auto_gain.unwrap_or(false)
+    }
+
+    /// How peaks beyond full scale are limited in the 16-bit audio sent to Soniox. Defaults to hard.
+    pub fn limiter(&self) -> LimiterMode {
+        self.limiter.unwrap_or_default()
+    }
+
+    /// Defaults to false.
     pub fn show_window_border(&self) -> bool {
-        self.show_window_border.expect("Validated")
+        self.show_window_border.unwrap_or(false)
     }
 
+    /// Inner size of the debug window in points. Defaults to 300 x 500.
+    pub fn debug_window_size(&self) -> (f32, f32) {
+        self.debug_window_size.unwrap_or((300.0, 500.0))
+    }
+
+    /// Desktop position of the debug window's top-left corner, in points. `None` lets the OS place it.
+    pub fn debug_window_pos(&self) -> Option<(f32, f32)> {
+        self.debug_window_pos
+    }
+
+    /// Defaults to `problems`.
+    pub fn show_status_indicator(&self) -> StatusIndicator {
+        self.show_status_indicator.unwrap_or_default()
+    }
+
+    /// Corner of the overlay the status dot sits in. Defaults to `top_right`.
+    pub fn status_indicator_corner(&self) -> Align2 {
+        match self.status_indicator_corner.as_deref().unwrap_or("top_right") {
+            "top_left" => Align2::LEFT_TOP,
+            "bottom_left" => Align2::LEFT_BOTTOM,
+            "bottom_right" => Align2::RIGHT_BOTTOM,
+            _ => Align2::RIGHT_TOP,
+        }
+    }
+
+    /// Defaults to 10.
+    pub fn max_reconnects(&self) -> u32 {
+        self.max_reconnects.unwrap_or(10)
+    }
+
+    /// Interval between websocket keepalive pings. Defaults to 15s; `keepalive_interval_secs = 0` disables them.
+    pub fn keepalive_interval(&self) -> Option<std::time::Duration> {
+        match self.keepalive_interval_secs.unwrap_or(15) {
+            0 => None,
+            secs => Some(std::time::Duration::from_secs(secs)),
+        }
+    }
+
+    /// Audio (ms) collected before one websocket frame is sent. Defaults to 100; 0 sends every packet.
+    pub fn audio_chunk_ms(&self) -> u64 {
+        self.audio_chunk_ms.unwrap_or(100)
+    }
+
+    /// How long the capture may deliver nothing but empty buffers before the stream ends.
+    /// Defaults to 2s; `empty_audio_timeout_ms = 0` never ends it that way.
+    pub fn empty_audio_timeout(&self) -> Option<std::time::Duration> {
+        match self.empty_audio_timeout_ms.unwrap_or(2_000) {
+            0 => None,
+            ms => Some(std::time::Duration::from_millis(ms)),
+        }
+    }
+
+    /// Defaults to false.
     pub fn enable_raw_logging(&self) -> bool {
-        self.enable_raw_logging.expect("Validated")
+        self.enable_raw_logging.unwrap_or(false)
     }
 
+    /// Defaults to false.
     pub fn enable_audio_logging(&self) -> bool {
-        self.enable_audio_logging.expect("Validated")
+        self.enable_audio_logging.unwrap_or(false)
+    }
+
+    /// Sent to Soniox as `client_reference_id`. Defaults to an id generated at launch.
+    pub fn session_id(&self) -> &str {
+        self.session_id.as_deref().unwrap_or(&self.generated_session_id)
     }
 
+    /// Defaults to false.
     pub fn save_transcription(&self) -> bool {
-        self.save_transcription.expect("Validated")
+        self.save_transcription.unwrap_or(false)
     }
 
+    /// Defaults to `transcript.txt`.
     pub fn transcript_save_path(&self) -> &str {
-        self.transcript_save_path.as_ref().expect("Validated")
+        self.transcript_save_path.as_deref().unwrap_or("transcript.txt")
+    }
+
+    /// Layout of the live transcript file. Defaults to plain text.
+    pub fn transcript_format(&self) -> TranscriptFormat {
+        self.transcript_format.unwrap_or_default()
+    }
+
+    /// Subtitle file (next to `transcript_save_path`) written on exit. None (default) disables export.
+    pub fn export_format(&self) -> Option<ExportFormat> {
+        self.export_format
+    }
+
+    /// Local port for the WebSocket caption server. None (default) disables it.
+    pub fn caption_server_port(&self) -> Option<u16> {
+        self.caption_server_port
+    }
+
+    /// File kept in sync with the visible lines, for an OBS text source. None (default) disables it.
+    pub fn obs_text_file(&self) -> Option<&str> {
+        self.obs_text_file.as_deref()
+    }
+
+    /// Terms masked in the overlay. Reads the file if `word_filter` is a path; empty if unset.
+    pub fn word_filter(&self) -> Result<Vec<String>, SonioxWindowsErrors> {
+        match &self.word_filter {
+            None => Ok(Vec::new()),
+            Some(WordFilterSetting::Inline(terms)) => Ok(terms.clone()),
+            Some(WordFilterSetting::File(path)) => {
+                let contents = std::fs::read_to_string(path).map_err(|e| {
+                    SonioxWindowsErrors::Internal(format!("Failed to read word_filter file '{}': {}", path, e))
+                })?;
+                Ok(contents.lines().map(str::to_string).collect())
+            }
+        }
+    }
+}
+
+/// Launch timestamp plus a short random suffix, e.g. `20250101-120000-3fa9c2`.
+fn generate_session_id() -> String {
+    let random = RandomState::new().build_hasher().finish();
+    format!("{}-{:06x}", chrono::Local::now().format("%Y%m%d-%H%M%S"), random & 0xff_ffff)
+}
+
+/// A validated hotkey setting, `default` when unset, or `None` when set to an empty string.
+fn hotkey_or(setting: &Option<String>, default: &str) -> Option<Hotkey> {
+    let combo = setting.as_deref().unwrap_or(default);
+    (!combo.is_empty()).then(|| parse_hotkey(combo).expect("Validated"))
+}
+
+pub fn save_window_offset(path: &str, offset: (f32, f32)) -> std::io::Result<()> {
+    save_setting(path, "window_offset", &format!("[{:.1}, {:.1}]", offset.0, offset.1))
+}
+
+pub fn save_window_size(path: &str, size: (f32, f32)) -> std::io::Result<()> {
+    save_setting(path, "window_width", &format!("{:.1}", size.0))?;
+    save_setting(path, "window_height", &format!("{:.1}", size.1))
+}
+
+pub fn save_font_size(path: &str, font_size: f32) -> std::io::Result<()> {
+    save_setting(path, "font_size", &format!("{:.1}", font_size))
+}
+
+/// Rewrites the `key = ...` line of the config file at `path` with `value` (a TOML literal),
+/// keeping everything else, comments included, as is. The key is appended if the file doesn't have it yet.
+fn save_setting(path: &str, key: &str, value: &str) -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(path)?;
+    let line = format!("{} = {}", key, value);
+    let mut replaced = false;
+    let mut lines: Vec<String> = contents
+        .lines()
+        .map(|l| {
+            let trimmed = l.trim_start();
+            if !replaced && trimmed.starts_with(key) && trimmed[key.len()..].trim_start().starts_with('=') {
+                replaced = true;
+                line.clone()
+            } else {
+                l.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(line);
+    }
+    let mut out = lines.join("\n");
+    if contents.ends_with('\n') {
+        out.push('\n');
+    }
+    std::fs::write(path, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A config file of its own in the temp dir, removed again when the test ends.
+    struct TempConfig(String);
+
+    impl TempConfig {
+        fn new(name: &str, contents: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("sonilivetext-{}-{}.toml", name, std::process::id()));
+            std::fs::write(&path, contents).unwrap();
+            Self(path.to_string_lossy().into_owned())
+        }
+    }
+
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn saved_settings_survive_a_reload() {
+        let config = TempConfig::new(
+            "round-trip",
+            concat!(
+                "# Captions\n",
+                "api_key = \"test\"\n",
+                "language_hints = [\"en\"]\n",
+                "font_size = 24.0\n",
+                "font_size_overrides = { ja = 30.0 }\n",
+                "window_width = 800.0\n",
+            ),
+        );
+        let before = SettingsApp::new(&config.0).unwrap();
+
+        save_font_size(&config.0, 31.5).unwrap();
+        save_window_size(&config.0, (1024.0, 480.0)).unwrap();
+        save_window_offset(&config.0, (-12.0, 40.5)).unwrap();
+        let after = SettingsApp::new(&config.0).unwrap();
+
+        assert_eq!(after.font_size(), 31.5);
+        assert_eq!((after.window_width(), after.window_height()), (1024.0, 480.0));
+        assert_eq!(after.window_offset(), (-12.0, 40.5));
+        // Neighbouring lines, including ones whose key merely starts with the saved one, are kept.
+        assert_eq!(after.language_hints(), before.language_hints());
+        assert_eq!(after.font_size_overrides(), before.font_size_overrides());
+        let contents = std::fs::read_to_string(&config.0).unwrap();
+        assert!(contents.starts_with("# Captions\n"));
+        assert!(contents.ends_with('\n'));
+        assert_eq!(contents.matches("font_size =").count(), 1);
+
+        // Of what the app saves at runtime, only the size of a locked window waits for a restart.
+        assert_eq!(before.restart_required_changes(&after), ["window_width/window_height"]);
     }
 }
@@ -1,6 +1,65 @@
 use crate::types::languages::LanguageHint;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationType {
+    /// Everything is translated into `target_language`.
+    #[default]
+    OneWay,
+    /// `language_a` and `language_b` are translated into each other.
+    TwoWay,
+}
+
+impl TranslationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranslationType::OneWay => "one_way",
+            TranslationType::TwoWay => "two_way",
+        }
+    }
+}
+
+/// What a translation shows on screen.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslateDisplay {
+    /// Only the translated text.
+    #[default]
+    TranslationOnly,
+    /// The original speech as a small line above each translated block.
+    Both,
+    /// The original speech in the left half of the overlay, each translated block level with it on the right.
+    SideBySide,
+}
+
+impl TranslateDisplay {
+    /// Whether the original speech is kept alongside the translation.
+    pub fn keeps_source(&self) -> bool {
+        *self != TranslateDisplay::TranslationOnly
+    }
+}
+
+/// Commands sent from the overlay to the running Soniox stream.
+#[derive(Debug, Clone)]
+pub enum ControlMsg {
+    /// Reconnect using a different model.
+    SetModel(String),
+    /// Stop (or resume) sending audio while the overlay is paused. The connection stays open.
+    SetPaused(bool),
+}
+
+/// Stream health reported to the overlay's status indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamStatus {
+    /// Connected and audio is arriving from the capture.
+    Live,
+    /// Connected, but the capture has sent nothing for a while.
+    NoAudio,
+    /// Connecting, reconnecting, or given up.
+    Disconnected,
+}
+
 #[derive(Debug, Serialize, Default)]
 pub struct SonioxTranslationObject {
     pub r#type: &'static str,
@@ -49,3 +108,19 @@ pub struct SonioxTranscriptionResponse {
     pub total_audio_proc_ms: f64,
     pub finished: Option<bool>,
 }
+
+/// Error object Soniox sends on the text channel instead of tokens.
+#[derive(Debug, Deserialize)]
+pub struct SonioxErrorMessage {
+    #[serde(default)]
+    pub error_code: Option<u16>,
+    pub error_message: String,
+}
+
+/// Any text message from Soniox. `Error` is tried first because every response field is optional.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum SonioxMessage {
+    Error(SonioxErrorMessage),
+    Response(SonioxTranscriptionResponse),
+}
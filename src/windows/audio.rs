@@ -1,10 +1,12 @@
 use crate::errors::SonioxWindowsErrors;
-use crate::types::audio::AudioMessage;
+use crate::types::audio::{AudioCaptureMode, AudioMessage, CHANNELS, SAMPLE_RATE};
+use crate::windows::dsp::{LevelProcessor, Resampler};
+use crate::windows::utils::find_process_id;
 use bytemuck::cast_slice;
 use std::thread::{self, sleep};
 use std::time::Duration;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-use wasapi::{DeviceEnumerator, Direction, StreamMode, initialize_mta};
+use wasapi::{DeviceEnumerator, Direction, StreamMode, WasapiError, initialize_mta};
 use std::sync::mpsc::{channel, TryRecvError};
 
 #[derive(Debug)]
@@ -13,74 +15,434 @@ enum StartCaptureType {
     Loopback,
 }
 
+type DebugWavWriter = hound::WavWriter<std::io::BufWriter<std::fs::File>>;
+
+// Timestamped so repeated runs don't clobber each other's recordings.
+fn create_debug_wav_writer(channels: u16, sample_rate: u32) -> Option<DebugWavWriter> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let path = chrono::Local::now()
+        .format("debug_audio_%Y%m%d_%H%M%S.wav")
+        .to_string();
+    match hound::WavWriter::create(&path, spec) {
+        Ok(w) => {
+            log::info!("Recording captured audio to {}", path);
+            Some(w)
+        }
+        Err(e) => {
+            log::error!("Failed to create {}: {}", path, e);
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CaptureOptions {
+    pub input_mode: String,
+    pub enable_audio_logging: bool,
+    pub mic_gain: f32,
+    pub system_gain: f32,
+    pub max_system_buffer_ms: u64,
+    pub capture_mode: AudioCaptureMode,
+    pub noise_gate_threshold: f32,
+    pub auto_gain: bool,
+}
+
 pub fn start_capture_audio(
     tx_audio: UnboundedSender<AudioMessage>,
     rx_stop: UnboundedReceiver<bool>,
-    input_mode: &str,
-    enable_audio_logging: bool,
+    options: &CaptureOptions,
 ) -> Result<(), SonioxWindowsErrors> {
-    if input_mode == "both" {
-        start_dual_capture(tx_audio, rx_stop, enable_audio_logging)
+    if let Some(path) = options.input_mode.strip_prefix(FILE_INPUT_PREFIX) {
+        start_file_playback(tx_audio, rx_stop, path, options)
+    } else if options.input_mode == "both" {
+        start_dual_capture(tx_audio, rx_stop, options)
     } else {
-        start_single_capture(tx_audio, rx_stop, input_mode, enable_audio_logging)
+        start_single_capture(tx_audio, rx_stop, options)
     }
 }
 
-fn start_single_capture(
+/// `audio_input = "file:clip.wav"` plays a recording instead of capturing a device.
+const FILE_INPUT_PREFIX: &str = "file:";
+/// Amount of audio sent per message during file playback.
+const FILE_PLAYBACK_CHUNK: Duration = Duration::from_millis(20);
+
+/// Streams a WAV file through the same resample/gate path as live capture, paced in real time,
+/// so transcription issues can be reproduced from a recorded clip.
+fn start_file_playback(
     tx_audio: UnboundedSender<AudioMessage>,
     mut rx_stop: UnboundedReceiver<bool>,
+    path: &str,
+    options: &CaptureOptions,
+) -> Result<(), SonioxWindowsErrors> {
+    let file_error = |e: hound::Error| SonioxWindowsErrors::Internal(format!("Failed to read '{}': {}", path, e));
+    let mut reader = hound::WavReader::open(path).map_err(file_error)?;
+    let spec = reader.spec();
+    log::info!(
+        "Playing '{}' ({}Hz, {}ch, {}-bit {:?})",
+        path, spec.sample_rate, spec.channels, spec.bits_per_sample, spec.sample_format
+    );
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .map_err(file_error)?;
+
+    let mut resampler = Resampler::new(spec.sample_rate, spec.channels, SAMPLE_RATE);
+    let mut levels = LevelProcessor::new(options.noise_gate_threshold, options.auto_gain);
+    let frames_per_chunk = (spec.sample_rate as u128 * FILE_PLAYBACK_CHUNK.as_millis() / 1000).max(1) as usize;
+    let started = std::time::Instant::now();
+
+    for (index, chunk) in samples.chunks(frames_per_chunk * spec.channels as usize).enumerate() {
+        if let Ok(true) = rx_stop.try_recv() {
+            log::info!("File playback stopped.");
+            break;
+        }
+        let mut data = resampler.process(chunk);
+        levels.process(&mut data);
+        if !data.is_empty() && tx_audio.send(AudioMessage::Audio(data)).is_err() {
+            break;
+        }
+        let due = started + FILE_PLAYBACK_CHUNK * (index as u32 + 1);
+        if let Some(wait) = due.checked_duration_since(std::time::Instant::now()) {
+            sleep(wait);
+        }
+    }
+
+    log::info!("File playback finished.");
+    let _ = tx_audio.send(AudioMessage::Stop);
+    Ok(())
+}
+
+/// Friendly names of all active capture (microphone) and render (loopback) endpoints.
+/// Any of these names can be used as `audio_input` in addition to the special modes.
+pub fn list_audio_devices() -> Vec<(String, Direction)> {
+    let _ = initialize_mta().ok();
+    let mut devices = Vec::new();
+    let enumerator = match DeviceEnumerator::new() {
+        Ok(e) => e,
+        Err(e) => {
+            log::error!("Failed to create device enumerator: {}", e);
+            return devices;
+        }
+    };
+
+    for direction in [Direction::Capture, Direction::Render] {
+        let collection = match enumerator.get_device_collection(&direction) {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Failed to enumerate {:?} devices: {}", direction, e);
+                continue;
+            }
+        };
+        let count = collection.get_nbr_devices().unwrap_or(0);
+        for index in 0..count {
+            match collection.get_device_at_index(index).and_then(|d| d.get_friendlyname()) {
+                Ok(name) => devices.push((name, direction)),
+                Err(e) => log::warn!("Failed to read {:?} device #{}: {}", direction, index, e),
+            }
+        }
+    }
+    devices
+}
+
+/// Which kind of device failed, for errors shown to the user.
+fn device_label(direction: &Direction) -> &'static str {
+    match direction {
+        Direction::Capture => "capture device (microphone)",
+        Direction::Render => "render device (system loopback)",
+    }
+}
+
+fn device_error(direction: &Direction, err: WasapiError) -> SonioxWindowsErrors {
+    SonioxWindowsErrors::Internal(format!("No usable {}: {}", device_label(direction), err))
+}
+
+/// Resolves `audio_input` to a device: "microphone" is the default capture device,
+/// a friendly name selects that endpoint, anything else is the default render (loopback) device.
+fn open_input_device(
+    enumerator: &DeviceEnumerator,
     input_mode: &str,
-    enable_audio_logging: bool,
+) -> Result<wasapi::Device, SonioxWindowsErrors> {
+    match input_mode {
+        "microphone" => {
+            return enumerator
+                .get_default_device(&Direction::Capture)
+                .map_err(|e| device_error(&Direction::Capture, e));
+        }
+        "loopback" | "default" | "Default" => {}
+        name => {
+            for direction in [Direction::Capture, Direction::Render] {
+                let collection = enumerator.get_device_collection(&direction)?;
+                if let Ok(device) = collection.get_device_with_name(name) {
+                    log::info!("Using {:?} device: {}", direction, name);
+                    return Ok(device);
+                }
+            }
+            log::warn!("Audio device '{}' not found, falling back to system loopback", name);
+        }
+    }
+    enumerator
+        .get_default_device(&Direction::Render)
+        .map_err(|e| device_error(&Direction::Render, e))
+}
+
+const PROCESS_INPUT_PREFIX: &str = "process:";
+/// Rate requested from a process loopback client, which resamples to it.
+const PROCESS_LOOPBACK_RATE: usize = 48_000;
+
+/// Activates a loopback client that only hears `exe_name` and its child processes
+/// (Windows 10 20H2 or later). Returns None, with a warning, when the process is not running
+/// or activation fails, so the caller falls back to system loopback.
+fn open_process_loopback(exe_name: &str) -> Option<wasapi::AudioClient> {
+    let Some(pid) = find_process_id(exe_name) else {
+        log::warn!("Process '{}' is not running, falling back to system loopback", exe_name);
+        return None;
+    };
+    match wasapi::AudioClient::new_application_loopback_client(pid, true) {
+        Ok(client) => {
+            log::info!("Capturing audio of {} (pid {})", exe_name, pid);
+            Some(client)
+        }
+        Err(e) => {
+            log::warn!("Process loopback for {} failed ({}), falling back to system loopback", exe_name, e);
+            None
+        }
+    }
+}
+
+/// Sample layout delivered by the capture client.
+#[derive(Debug, Clone, Copy)]
+enum SampleEncoding {
+    Float32,
+    Int16,
+    Int32,
+}
+
+impl SampleEncoding {
+    fn bytes_per_sample(self) -> usize {
+        match self {
+            SampleEncoding::Int16 => 2,
+            SampleEncoding::Float32 | SampleEncoding::Int32 => 4,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> Vec<f32> {
+        match self {
+            SampleEncoding::Float32 => cast_slice::<u8, f32>(bytes).to_vec(),
+            SampleEncoding::Int16 => bytes
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                .collect(),
+            // 24-bit samples arrive left-justified in a 32-bit container, so one scale fits both.
+            SampleEncoding::Int32 => bytes
+                .chunks_exact(4)
+                .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0)
+                .collect(),
+        }
+    }
+}
+
+/// Finds a format the device accepts in exclusive mode, at its native rate and channel count.
+fn negotiate_exclusive_format(
+    audio_client: &wasapi::AudioClient,
+) -> Result<(wasapi::WaveFormat, SampleEncoding, StreamMode), SonioxWindowsErrors> {
+    let mix = audio_client.get_mixformat()?;
+    let (rate, channels) = (mix.get_samplespersec() as usize, mix.get_nchannels() as usize);
+    let candidates = [
+        (32, 32, wasapi::SampleType::Float, SampleEncoding::Float32),
+        (16, 16, wasapi::SampleType::Int, SampleEncoding::Int16),
+        (32, 24, wasapi::SampleType::Int, SampleEncoding::Int32),
+        (32, 32, wasapi::SampleType::Int, SampleEncoding::Int32),
+    ];
+
+    for (store_bits, valid_bits, sample_type, encoding) in candidates {
+        let wanted = wasapi::WaveFormat::new(store_bits, valid_bits, &sample_type, rate, channels, None);
+        if let Ok(format) = audio_client.is_supported_exclusive_with_quirks(&wanted) {
+            let (_, min_period) = audio_client.get_device_period()?;
+            log::info!(
+                "Exclusive mode: {}-bit {:?} {}Hz {}ch, period {} hns",
+                valid_bits, encoding, rate, channels, min_period
+            );
+            return Ok((format, encoding, StreamMode::PollingExclusive { period_hns: min_period }));
+        }
+    }
+
+    Err(SonioxWindowsErrors::AudioConfig(format!(
+        "The audio device does not support exclusive capture at {}Hz {}ch. Set capture_mode = \"shared\".",
+        rate, channels
+    )))
+}
+
+/// Capture is re-established this many times in a row before giving up.
+const MAX_RECOVERY_ATTEMPTS: u32 = 10;
+const RECOVERY_BACKOFF: Duration = Duration::from_millis(500);
+
+enum SessionEnd {
+    Stopped,
+}
+
+fn start_single_capture(
+    tx_audio: UnboundedSender<AudioMessage>,
+    mut rx_stop: UnboundedReceiver<bool>,
+    options: &CaptureOptions,
 ) -> Result<(), SonioxWindowsErrors> {
     initialize_mta()
         .ok()
         .map_err(|_| SonioxWindowsErrors::Internal("".to_string()))?;
-    let enumerator = DeviceEnumerator::new()?;
-    
-    let direction = if input_mode == "microphone" {
-        Direction::Capture
-    } else {
-        Direction::Render
-    };
-    
-    let device = enumerator.get_default_device(&direction)?;
-    let mut audio_client = device.get_iaudioclient()?;
-    let format = audio_client.get_mixformat()?;
-    let bytes_per_frame = format.get_blockalign() as usize;
 
-    let mode = StreamMode::PollingShared {
-        autoconvert: false,
-        buffer_duration_hns: 1_000_000,
-    };
-    audio_client.initialize_client(&format, &Direction::Capture, &mode)?;
+    let mut wav_writer: Option<DebugWavWriter> = None;
+    let mut attempts = 0;
+    loop {
+        let mut started = false;
+        let result = run_single_session(
+            &tx_audio,
+            &mut rx_stop,
+            options,
+            &mut wav_writer,
+            &mut started,
+        );
+        match result {
+            Ok(SessionEnd::Stopped) => break,
+            // The settings are wrong, not the device: every attempt would fail the same way.
+            Err(err @ SonioxWindowsErrors::AudioConfig(_)) => return Err(err),
+            Err(err) => {
+                if started {
+                    attempts = 0;
+                }
+                attempts += 1;
+                if attempts > MAX_RECOVERY_ATTEMPTS {
+                    log::error!("Audio capture could not be recovered after {} attempts", MAX_RECOVERY_ATTEMPTS);
+                    return Err(err);
+                }
+                log::warn!(
+                    "Audio capture lost ({}). Recovery attempt {}/{} in {:?}...",
+                    err, attempts, MAX_RECOVERY_ATTEMPTS, RECOVERY_BACKOFF
+                );
+                sleep(RECOVERY_BACKOFF);
+                if let Ok(true) = rx_stop.try_recv() {
+                    log::info!("Audio thread terminated during recovery!");
+                    break;
+                }
+            }
+        }
+    }
 
-    let capture = audio_client.get_audiocaptureclient()?;
-    audio_client.start_stream()?;
+    let _ = tx_audio.send(AudioMessage::Stop);
+    Ok(())
+}
 
-    // Initialize WAV writer for debugging
-    let mut wav_writer = if enable_audio_logging {
-        let spec = hound::WavSpec {
-            channels: format.get_nchannels(),
-            sample_rate: format.get_samplespersec(),
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
-        match hound::WavWriter::create("debug_audio.wav", spec) {
-            Ok(w) => Some(w),
-            Err(e) => {
-                log::error!("Failed to create debug_audio.wav: {}", e);
-                None
+/// Runs one capture session against the device selected by `input_mode`.
+/// Returns an error when the device goes away so the caller can re-enumerate.
+fn run_single_session(
+    tx_audio: &UnboundedSender<AudioMessage>,
+    rx_stop: &mut UnboundedReceiver<bool>,
+    options: &CaptureOptions,
+    wav_writer: &mut Option<DebugWavWriter>,
+    started: &mut bool,
+) -> Result<SessionEnd, SonioxWindowsErrors> {
+    let input_mode = options.input_mode.as_str();
+    let process_client = input_mode.strip_prefix(PROCESS_INPUT_PREFIX).and_then(open_process_loopback);
+    let event_driven = process_client.is_some();
+
+    let (mut audio_client, format, encoding, mode) = match process_client {
+        // Process loopback clients have no mix format to query; they convert to what is asked for.
+        Some(audio_client) => {
+            if options.capture_mode == AudioCaptureMode::Exclusive {
+                log::warn!("capture_mode = \"exclusive\" does not apply to process loopback, capturing shared");
             }
+            let format = wasapi::WaveFormat::new(32, 32, &wasapi::SampleType::Float, PROCESS_LOOPBACK_RATE, 2, None);
+            let mode = StreamMode::EventsShared {
+                autoconvert: true,
+                buffer_duration_hns: 0,
+            };
+            (audio_client, format, SampleEncoding::Float32, mode)
+        }
+        None => {
+            let device_input = if input_mode.starts_with(PROCESS_INPUT_PREFIX) { "loopback" } else { input_mode };
+            let enumerator = DeviceEnumerator::new()?;
+            let device = open_input_device(&enumerator, device_input)?;
+            let direction = device.get_direction();
+            let audio_client = device.get_iaudioclient().map_err(|e| device_error(&direction, e))?;
+            let (format, encoding, mode) = match options.capture_mode {
+                AudioCaptureMode::Shared => (
+                    audio_client.get_mixformat().map_err(|e| device_error(&direction, e))?,
+                    SampleEncoding::Float32,
+                    StreamMode::PollingShared {
+                        autoconvert: false,
+                        buffer_duration_hns: 1_000_000,
+                    },
+                ),
+                AudioCaptureMode::Exclusive => {
+                    if direction == Direction::Render {
+                        return Err(SonioxWindowsErrors::AudioConfig(
+                            "Exclusive capture mode is not available for loopback devices. Set capture_mode = \"shared\" or pick an input device.".to_string(),
+                        ));
+                    }
+                    negotiate_exclusive_format(&audio_client)?
+                }
+            };
+            log::debug!(
+                "Detected {} format: {}Hz {}ch {}-bit",
+                device_label(&direction),
+                format.get_samplespersec(),
+                format.get_nchannels(),
+                format.get_bitspersample()
+            );
+            (audio_client, format, encoding, mode)
         }
-    } else {
-        None
     };
+    let bytes_per_frame = format.get_blockalign() as usize;
 
-    log::info!("Started single audio stream: {}", input_mode);
-    loop {
+    audio_client
+        .initialize_client(&format, &Direction::Capture, &mode)
+        .map_err(|e| match options.capture_mode {
+            AudioCaptureMode::Exclusive => SonioxWindowsErrors::Internal(format!(
+                "The audio device rejected exclusive access (is another application using it?): {}",
+                e
+            )),
+            AudioCaptureMode::Shared => e.into(),
+        })?;
+
+    // Process loopback only runs in event mode. The loop below still polls, but the handle
+    // has to stay alive for as long as the stream does.
+    let _event_handle = if event_driven { Some(audio_client.set_get_eventhandle()?) } else { None };
+    let capture = audio_client.get_audiocaptureclient()?;
+    audio_client.start_stream()?;
+    *started = true;
+
+    // Initialize WAV writer for debugging (once, on the first successful session)
+    if options.enable_audio_logging && wav_writer.is_none() {
+        *wav_writer = create_debug_wav_writer(CHANNELS, SAMPLE_RATE);
+    }
+
+    // The device runs at its native mix format; Soniox always gets 16kHz mono.
+    let mut resampler = Resampler::new(format.get_samplespersec(), format.get_nchannels(), SAMPLE_RATE);
+    let mut levels = LevelProcessor::new(options.noise_gate_threshold, options.auto_gain);
+
+    log::info!(
+        "Started single audio stream: {} ({}Hz {}ch -> {}Hz {}ch)",
+        input_mode,
+        format.get_samplespersec(),
+        format.get_nchannels(),
+        SAMPLE_RATE,
+        CHANNELS
+    );
+    let end = loop {
         if let Ok(true) = rx_stop.try_recv() {
             log::info!("Audio thread terminated!");
-            break;
+            break SessionEnd::Stopped;
         }
 
         let frames = match capture.get_next_packet_size()? {
@@ -94,15 +456,21 @@ fn start_single_capture(
         let mut buffer = vec![0u8; frames as usize * bytes_per_frame];
         let _ = capture.read_from_device(&mut buffer)?;
 
-        let final_buffer: Vec<f32> = if !buffer.len().is_multiple_of(4) {
-            log::warn!("Buffer size not multiple of 4: {}", buffer.len());
+        let mut final_buffer: Vec<f32> = if !buffer.len().is_multiple_of(encoding.bytes_per_sample()) {
+            log::warn!("Buffer size not multiple of {}: {}", encoding.bytes_per_sample(), buffer.len());
             Vec::new()
         } else {
-            cast_slice::<u8, f32>(&buffer).to_vec()
+            resampler.process(&encoding.decode(&buffer))
         };
 
+        if final_buffer.is_empty() {
+            // An empty buffer would end the Soniox stream; wait for the next packet instead.
+            continue;
+        }
+        levels.process(&mut final_buffer);
+
         // Write to WAV for debugging
-        if let Some(writer) = &mut wav_writer {
+        if let Some(writer) = wav_writer {
             for &sample in &final_buffer {
                  let amplitude = (sample * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
                  if let Err(e) = writer.write_sample(amplitude) {
@@ -114,19 +482,18 @@ fn start_single_capture(
 
         if let Err(err) = result {
             log::info!("Audio thread terminated, error: {:?}", err);
-            break;
+            break SessionEnd::Stopped;
         }
-    }
+    };
 
     audio_client.stop_stream()?;
-    let _ = tx_audio.send(AudioMessage::Stop);
-    Ok(())
+    Ok(end)
 }
 
 fn start_dual_capture(
     tx_audio: UnboundedSender<AudioMessage>,
     mut rx_stop: UnboundedReceiver<bool>,
-    enable_audio_logging: bool,
+    options: &CaptureOptions,
 ) -> Result<(), SonioxWindowsErrors> {
     initialize_mta()
         .ok()
@@ -134,6 +501,37 @@ fn start_dual_capture(
 
     log::info!("Initializing Dual Capture Mode...");
 
+    // The mixer is clocked by the mic, so a missing device would stall it (no mic) or only ever
+    // add silence (no playback device). Capture whichever source exists on its own instead.
+    let enumerator = DeviceEnumerator::new()?;
+    let has_mic = enumerator.get_default_device(&Direction::Capture).is_ok();
+    let has_system = enumerator.get_default_device(&Direction::Render).is_ok();
+    if !has_mic && !has_system {
+        return Err(SonioxWindowsErrors::Internal(
+            "audio_input = \"both\" found neither a microphone nor a playback device".to_string(),
+        ));
+    }
+    if has_mic != has_system {
+        let (available, missing) = if has_mic { ("microphone", "playback") } else { ("loopback", "microphone") };
+        log::warn!(
+            "audio_input = \"both\": no default {} device, capturing {} only",
+            missing,
+            available
+        );
+        let single = CaptureOptions {
+            input_mode: available.to_string(),
+            ..options.clone()
+        };
+        return start_single_capture(tx_audio, rx_stop, &single);
+    }
+
+    // The two streams are mixed in shared mode; see run_capture_session.
+    if options.capture_mode == AudioCaptureMode::Exclusive {
+        return Err(SonioxWindowsErrors::AudioConfig(
+            "Exclusive capture mode is not available with audio_input = \"both\". Set capture_mode = \"shared\".".to_string(),
+        ));
+    }
+
     let (tx_mic_internal, rx_mic_internal) = channel::<Vec<f32>>();
     let (tx_sys_internal, rx_sys_internal) = channel::<Vec<f32>>();
 
@@ -157,30 +555,21 @@ fn start_dual_capture(
         }
     });
 
-    log::info!("Mixer Loop Starting...");
+    let (mic_gain, system_gain) = (options.mic_gain, options.system_gain);
+    log::info!("Mixer Loop Starting... (mic_gain: {}, system_gain: {})", mic_gain, system_gain);
 
     // Initialize WAV writer
-    let mut wav_writer = if enable_audio_logging {
-        let spec = hound::WavSpec {
-            channels: 1,
-            sample_rate: 16000,
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
-        match hound::WavWriter::create("debug_audio.wav", spec) {
-            Ok(w) => Some(w),
-            Err(e) => {
-                log::error!("Failed to create debug_audio.wav: {}", e);
-                None
-            }
-        }
+    let mut wav_writer = if options.enable_audio_logging {
+        create_debug_wav_writer(CHANNELS, SAMPLE_RATE)
     } else {
         None
     };
 
     // --- 3. Mixer Loop ---
     let mut sys_buffer: Vec<f32> = Vec::new();
-    const MAX_SYS_BUFFER_SIZE: usize = 48000 * 2; 
+    // Bounded by time so the allowed system-vs-mic skew doesn't depend on the sample rate.
+    let mut levels = LevelProcessor::new(options.noise_gate_threshold, options.auto_gain);
+    let max_sys_buffer_size = (SAMPLE_RATE as u64 * options.max_system_buffer_ms / 1000) as usize;
 
     loop {
         if let Ok(true) = rx_stop.try_recv() {
@@ -209,9 +598,17 @@ fn start_dual_capture(
             }
         }
         
-        if sys_buffer.len() > MAX_SYS_BUFFER_SIZE {
-             let excess = sys_buffer.len() - MAX_SYS_BUFFER_SIZE;
-             sys_buffer.drain(0..excess);
+        if sys_buffer.len() > max_sys_buffer_size {
+             // Drop whole mic-packet-sized chunks from the front to stay aligned with the master clock.
+             let packet = mic_chunk.len().max(1);
+             let excess = sys_buffer.len() - max_sys_buffer_size;
+             let dropped = (excess.div_ceil(packet) * packet).min(sys_buffer.len());
+             sys_buffer.drain(0..dropped);
+             log::debug!(
+                 "Mixer: system audio ahead of mic, dropped {} samples ({} ms)",
+                 dropped,
+                 dropped as u64 * 1000 / SAMPLE_RATE as u64
+             );
         }
 
 
@@ -231,17 +628,17 @@ fn start_dual_capture(
 
         let mut max_amp = 0.0f32;
         for i in 0..frames_to_mix {
-            let mic_sample = mic_chunk[i];
-            let sys_sample = sys_part[i];
+            let mic_sample = mic_chunk[i] * mic_gain;
+            let sys_sample = sys_part[i] * system_gain;
             
-            // Sum and clamp
+            // Peaks beyond full scale are left to the limiter in the stream's PCM conversion.
             let sum = mic_sample + sys_sample;
-            // Hard clamp
-            let clamped = if sum > 1.0 { 1.0 } else if sum < -1.0 { -1.0 } else { sum };
-            mixed_chunk.push(clamped);
-            if clamped.abs() > max_amp { max_amp = clamped.abs(); }
+            mixed_chunk.push(sum);
+            if sum.abs() > max_amp { max_amp = sum.abs(); }
         }
 
+        levels.process(&mut mixed_chunk);
+
         // Reduced log frequency: log only if amp > 0.01 (silence is usually near 0)
         if max_amp > 0.001 {
              log::debug!("Mixer chunk positive. Max Amp: {}", max_amp);
@@ -282,7 +679,36 @@ fn run_capture_loop(
     tx: std::sync::mpsc::Sender<Vec<f32>>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let _ = initialize_mta().ok(); 
-    
+
+    let mut attempts = 0;
+    loop {
+        let mut started = false;
+        match run_capture_session(&capture_type, &tx, &mut started) {
+            Ok(SessionEnd::Stopped) => return Ok(()),
+            Err(err) => {
+                if started {
+                    attempts = 0;
+                }
+                attempts += 1;
+                if attempts > MAX_RECOVERY_ATTEMPTS {
+                    log::error!("[{:?}] Capture could not be recovered after {} attempts", capture_type, MAX_RECOVERY_ATTEMPTS);
+                    return Err(err);
+                }
+                log::warn!(
+                    "[{:?}] Capture lost ({}). Recovery attempt {}/{} in {:?}...",
+                    capture_type, err, attempts, MAX_RECOVERY_ATTEMPTS, RECOVERY_BACKOFF
+                );
+                sleep(RECOVERY_BACKOFF);
+            }
+        }
+    }
+}
+
+fn run_capture_session(
+    capture_type: &StartCaptureType,
+    tx: &std::sync::mpsc::Sender<Vec<f32>>,
+    started: &mut bool,
+) -> Result<SessionEnd, Box<dyn std::error::Error + Send + Sync>> {
     let enumerator = DeviceEnumerator::new()?;
     
     // Change: Use Role::Console (Default) for both to match single-mode behavior
@@ -305,8 +731,8 @@ fn run_capture_loop(
         32, 
         32, 
         &wasapi::SampleType::Float,
-        16000, 
-        1, 
+        SAMPLE_RATE as usize, 
+        CHANNELS as usize, 
         None 
     );
     
@@ -320,13 +746,14 @@ fn run_capture_loop(
     audio_client.initialize_client(&wave_format, &Direction::Capture, &mode)?;
     let capture = audio_client.get_audiocaptureclient()?;
     audio_client.start_stream()?;
+    *started = true;
     log::info!("[{:?}] Stream started successfully!", capture_type);
     
     let bytes_per_frame = 4; // f32
 
     let mut first_packet = true;
 
-    loop {
+    let result = loop {
          let packet_size = match capture.get_next_packet_size() {
              Ok(Some(s)) => s,
              Ok(None) => {
@@ -335,7 +762,7 @@ fn run_capture_loop(
              },
              Err(e) => {
                  log::error!("[{:?}] Capture error: {:?}", capture_type, e);
-                 break;
+                 break Err(e.into());
              }
          };
          
@@ -356,17 +783,17 @@ fn run_capture_loop(
                       let float_data: Vec<f32> = cast_slice::<u8, f32>(&buffer).to_vec();
                       if tx.send(float_data).is_err() {
                           log::warn!("[{:?}] Receiver closed, stopping thread.", capture_type);
-                          break; 
+                          break Ok(SessionEnd::Stopped);
                       }
                  }
              },
              Err(e) => {
                  log::warn!("[{:?}] Read error: {:?}", capture_type, e);
-                 break;
+                 break Err(e.into());
              }
          }
-    }
+    };
     
     audio_client.stop_stream().ok();
-    Ok(())
+    result
 }
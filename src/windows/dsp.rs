@@ -0,0 +1,166 @@
+use crate::types::audio::SAMPLE_RATE;
+
+/// Streaming linear resampler that downmixes interleaved frames to mono.
+///
+/// The fractional read position is carried across packets (together with the
+/// last input sample), so consecutive reads join up without drift or gaps.
+pub(crate) struct Resampler {
+    channels: usize,
+    step: f64,
+    position: f64,
+    last_sample: Option<f32>,
+}
+
+impl Resampler {
+    pub(crate) fn new(input_rate: u32, channels: u16, output_rate: u32) -> Self {
+        Self {
+            channels: channels.max(1) as usize,
+            step: input_rate as f64 / output_rate as f64,
+            position: 0.0,
+            last_sample: None,
+        }
+    }
+
+    pub(crate) fn process(&mut self, interleaved: &[f32]) -> Vec<f32> {
+        let mut input: Vec<f32> = Vec::with_capacity(interleaved.len() / self.channels + 1);
+        input.extend(self.last_sample);
+        input.extend(
+            interleaved
+                .chunks_exact(self.channels)
+                .map(|frame| frame.iter().sum::<f32>() / self.channels as f32),
+        );
+
+        if input.len() < 2 {
+            self.last_sample = input.last().copied();
+            return Vec::new();
+        }
+
+        let mut output = Vec::with_capacity((input.len() as f64 / self.step) as usize + 1);
+        while self.position + 1.0 < input.len() as f64 {
+            let index = self.position as usize;
+            let frac = (self.position - index as f64) as f32;
+            let (a, b) = (input[index], input[index + 1]);
+            output.push(a + (b - a) * frac);
+            self.position += self.step;
+        }
+
+        // The last input sample becomes index 0 of the next packet.
+        self.position -= (input.len() - 1) as f64;
+        self.last_sample = input.last().copied();
+        output
+    }
+}
+
+const GATE_ATTACK_MS: f32 = 5.0;
+const GATE_HOLD_MS: f32 = 200.0;
+const GATE_RELEASE_MS: f32 = 150.0;
+
+/// Envelope carried between packets by `apply_noise_gate`.
+#[derive(Debug, Default)]
+struct GateState {
+    gain: f32,
+    hold_remaining: usize,
+}
+
+/// Mutes packets whose RMS stays below `threshold` (0.0 disables the gate).
+///
+/// The gate opens within a few ms so word onsets survive, then holds and fades out
+/// slowly after the level drops, which avoids chopping the ends of words.
+/// Expects 16kHz mono samples, i.e. what every capture path produces.
+fn apply_noise_gate(samples: &mut [f32], threshold: f32, state: &mut GateState) {
+    if threshold <= 0.0 || samples.is_empty() {
+        return;
+    }
+
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    let samples_per_ms = SAMPLE_RATE as f32 / 1000.0;
+    if rms >= threshold {
+        state.hold_remaining = (GATE_HOLD_MS * samples_per_ms) as usize;
+    }
+
+    let open = state.hold_remaining > 0;
+    let attack_step = 1.0 / (GATE_ATTACK_MS * samples_per_ms);
+    let release_step = 1.0 / (GATE_RELEASE_MS * samples_per_ms);
+    for sample in samples.iter_mut() {
+        state.gain = if open {
+            (state.gain + attack_step).min(1.0)
+        } else {
+            (state.gain - release_step).max(0.0)
+        };
+        *sample *= state.gain;
+    }
+    state.hold_remaining = state.hold_remaining.saturating_sub(samples.len());
+}
+
+/// RMS level auto_gain steers toward (about -20 dBFS).
+const AGC_TARGET_RMS: f32 = 0.1;
+const AGC_MIN_GAIN: f32 = 0.25;
+const AGC_MAX_GAIN: f32 = 8.0;
+/// Packets quieter than this (or than the noise gate threshold) leave the gain where it is,
+/// so pauses don't wind it up and boost background noise.
+const AGC_SILENCE_RMS: f32 = 0.005;
+/// Gain drops quickly when speech gets loud and recovers slowly once it is quiet again.
+const AGC_ATTACK_MS: f32 = 50.0;
+const AGC_RELEASE_MS: f32 = 2_000.0;
+
+/// Gain carried between packets by `apply_auto_gain`.
+#[derive(Debug)]
+struct AgcState {
+    gain: f32,
+}
+
+impl Default for AgcState {
+    fn default() -> Self {
+        Self { gain: 1.0 }
+    }
+}
+
+/// Scales packets toward AGC_TARGET_RMS, never so far that the packet's peak would clip.
+/// The gain moves with slow attack/release and is ramped across each packet to avoid clicks.
+fn apply_auto_gain(samples: &mut [f32], silence_floor: f32, state: &mut AgcState) {
+    if samples.is_empty() {
+        return;
+    }
+
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    let start = state.gain;
+    if rms >= silence_floor.max(AGC_SILENCE_RMS) {
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let wanted = (AGC_TARGET_RMS / rms).min(1.0 / peak).clamp(AGC_MIN_GAIN, AGC_MAX_GAIN);
+        let time_constant = if wanted < state.gain { AGC_ATTACK_MS } else { AGC_RELEASE_MS };
+        let packet_ms = samples.len() as f32 * 1000.0 / SAMPLE_RATE as f32;
+        let blend = 1.0 - (-packet_ms / time_constant).exp();
+        state.gain += (wanted - state.gain) * blend;
+    }
+
+    let step = (state.gain - start) / samples.len() as f32;
+    for (i, sample) in samples.iter_mut().enumerate() {
+        *sample *= start + step * (i + 1) as f32;
+    }
+}
+
+/// Level processing shared by every capture path, applied to the 16kHz mono stream right
+/// before it is sent: the noise gate first, on the raw level its threshold was chosen for,
+/// then (with `auto_gain`) the automatic gain control.
+pub(crate) struct LevelProcessor {
+    gate_threshold: f32,
+    gate: GateState,
+    agc: Option<AgcState>,
+}
+
+impl LevelProcessor {
+    pub(crate) fn new(gate_threshold: f32, auto_gain: bool) -> Self {
+        Self {
+            gate_threshold,
+            gate: GateState::default(),
+            agc: auto_gain.then(AgcState::default),
+        }
+    }
+
+    pub(crate) fn process(&mut self, samples: &mut [f32]) {
+        apply_noise_gate(samples, self.gate_threshold, &mut self.gate);
+        if let Some(agc) = &mut self.agc {
+            apply_auto_gain(samples, self.gate_threshold, agc);
+        }
+    }
+}
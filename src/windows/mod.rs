@@ -1,2 +1,3 @@
-pub(crate) mod audio;
+pub mod audio;
+pub(crate) mod dsp;
 pub mod utils;
@@ -1,9 +1,22 @@
+use crate::types::settings::ZOrder;
 use eframe::Frame;
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
-use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::{CloseHandle, HWND, LPARAM, POINT, RECT};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW, TH32CS_SNAPPROCESS,
+};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOF_PRIMARY, ScreenToClient,
+};
+use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN,
+    RegisterHotKey, VK_CONTROL,
+};
 use windows::Win32::UI::WindowsAndMessaging::{
-    GWL_EXSTYLE, GWL_STYLE, GetSystemMetrics, GetWindowLongW, HWND_TOPMOST, MB_ICONERROR, MB_OK,
-    MessageBoxW, SM_CXSCREEN, SM_CYSCREEN, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_SHOWWINDOW,
+    GWL_EXSTYLE, GWL_STYLE, GetCursorPos, GetMessageW, GetSystemMetrics, GetWindowLongW, HWND_BOTTOM, HWND_NOTOPMOST, HWND_TOPMOST, MB_ICONERROR,
+    MB_OK, MSG, MessageBoxW, WM_HOTKEY, SM_CXSCREEN, SM_CYSCREEN, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_SHOWWINDOW,
     SetWindowLongW, SetWindowPos, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
     WS_EX_TRANSPARENT, WS_MAXIMIZEBOX, WS_MINIMIZEBOX,
 };
@@ -19,20 +32,51 @@ fn from_frame_to_hwnd(frame: &Frame) -> Option<HWND> {
     None
 }
 
-pub(crate) fn make_window_click_through(frame: &Frame) {
+/// Toggles WS_EX_TRANSPARENT so mouse input passes through the window (or not).
+pub(crate) fn make_window_click_through(frame: &Frame, enabled: bool) {
     if let Some(hwnd) = from_frame_to_hwnd(frame) {
         unsafe {
-            let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE);
-            SetWindowLongW(
-                hwnd,
-                GWL_EXSTYLE,
-                ex_style | WS_EX_LAYERED.0 as i32 | WS_EX_TRANSPARENT.0 as i32,
-            );
+            let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) | WS_EX_LAYERED.0 as i32;
+            let ex_style = if enabled {
+                ex_style | WS_EX_TRANSPARENT.0 as i32
+            } else {
+                ex_style & !(WS_EX_TRANSPARENT.0 as i32)
+            };
+            SetWindowLongW(hwnd, GWL_EXSTYLE, ex_style);
         }
     }
 }
 
-pub(crate) fn initialize_tool_window(frame: &Frame) {
+/// Mouse position relative to the window's client area, in physical pixels. Works while the
+/// window is click-through, when it gets no mouse messages of its own.
+pub(crate) fn cursor_client_pos(frame: &Frame) -> Option<(f32, f32)> {
+    let hwnd = from_frame_to_hwnd(frame)?;
+    let mut point = POINT::default();
+    unsafe {
+        GetCursorPos(&mut point).ok()?;
+        if !ScreenToClient(hwnd, &mut point).as_bool() {
+            return None;
+        }
+    }
+    Some((point.x as f32, point.y as f32))
+}
+
+/// Whether Ctrl is held right now, regardless of which window has focus.
+pub(crate) fn is_move_modifier_held() -> bool {
+    // SAFETY: GetAsyncKeyState only reads global key state; the high bit means "currently down".
+    unsafe { GetAsyncKeyState(VK_CONTROL.0 as i32) as u16 & 0x8000 != 0 }
+}
+
+/// The SetWindowPos insert-after handle that puts a window at `z_order`.
+fn z_order_handle(z_order: ZOrder) -> HWND {
+    match z_order {
+        ZOrder::Topmost => HWND_TOPMOST,
+        ZOrder::Normal => HWND_NOTOPMOST,
+        ZOrder::Bottom => HWND_BOTTOM,
+    }
+}
+
+pub(crate) fn initialize_tool_window(frame: &Frame, z_order: ZOrder) {
     if let Some(hwnd) = from_frame_to_hwnd(frame) {
         unsafe {
             let style = GetWindowLongW(hwnd, GWL_STYLE);
@@ -47,25 +91,28 @@ pub(crate) fn initialize_tool_window(frame: &Frame) {
                 GWL_EXSTYLE,
                 ex_style | WS_EX_TOOLWINDOW.0 as i32 | WS_EX_NOACTIVATE.0 as i32,
             );
-            let _ = SetWindowPos(
-                hwnd,
-                Some(HWND_TOPMOST),
-                0,
-                0,
-                0,
-                0,
-                SWP_NOMOVE | SWP_NOSIZE,
-            );
+            // This runs every frame; re-applying NOTOPMOST would keep raising a "normal" window.
+            if z_order != ZOrder::Normal {
+                let _ = SetWindowPos(
+                    hwnd,
+                    Some(z_order_handle(z_order)),
+                    0,
+                    0,
+                    0,
+                    0,
+                    SWP_NOMOVE | SWP_NOSIZE,
+                );
+            }
         }
     }
 }
 
-pub(crate) fn initialize_window(frame: &Frame) {
+pub(crate) fn initialize_window(frame: &Frame, z_order: ZOrder) {
     if let Some(hwnd) = from_frame_to_hwnd(frame) {
         unsafe {
             let _ = SetWindowPos(
                 hwnd,
-                Some(HWND_TOPMOST),
+                Some(z_order_handle(z_order)),
                 0,
                 0,
                 0,
@@ -92,6 +139,75 @@ pub fn show_error(msg: &str) {
     }
 }
 
+/// Bounds of one display in virtual-screen pixels; secondary monitors may have a negative origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub primary: bool,
+    /// Windows display scaling, e.g. 1.5 at 150%.
+    pub scale: f32,
+}
+
+impl MonitorRect {
+    /// The same rect in egui points, the unit window sizes and font sizes are given in.
+    pub fn in_points(&self) -> MonitorRect {
+        MonitorRect {
+            x: self.x / self.scale,
+            y: self.y / self.scale,
+            width: self.width / self.scale,
+            height: self.height / self.scale,
+            ..*self
+        }
+    }
+}
+
+/// Lists every display, in EnumDisplayMonitors order.
+pub fn enumerate_monitors() -> Vec<MonitorRect> {
+    unsafe extern "system" fn collect(monitor: HMONITOR, _hdc: HDC, _rect: *mut RECT, data: LPARAM) -> windows::core::BOOL {
+        // SAFETY: `data` is the &mut Vec passed to EnumDisplayMonitors below, alive for the whole call.
+        let monitors = unsafe { &mut *(data.0 as *mut Vec<MonitorRect>) };
+        let mut info = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+        if unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
+            let r = info.rcMonitor;
+            let (mut dpi_x, mut dpi_y) = (96, 96);
+            let _ = unsafe { GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) };
+            monitors.push(MonitorRect {
+                x: r.left as f32,
+                y: r.top as f32,
+                width: (r.right - r.left) as f32,
+                height: (r.bottom - r.top) as f32,
+                primary: info.dwFlags & MONITORINFOF_PRIMARY != 0,
+                scale: dpi_x as f32 / 96.0,
+            });
+        }
+        true.into()
+    }
+
+    let mut monitors: Vec<MonitorRect> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(None, None, Some(collect), LPARAM(&mut monitors as *mut _ as isize));
+    }
+    monitors
+}
+
+/// The monitor at `index`, or the primary one when no index is given or it is out of range.
+pub fn get_monitor_rect(index: Option<usize>) -> MonitorRect {
+    let monitors = enumerate_monitors();
+    if let Some(index) = index {
+        if let Some(monitor) = monitors.get(index) {
+            return *monitor;
+        }
+        log::warn!("monitor_index {} is out of range ({} monitors found); using the primary monitor", index, monitors.len());
+    }
+    monitors.iter().find(|m| m.primary).copied().unwrap_or_else(|| {
+        let (width, height) = get_screen_size();
+        MonitorRect { x: 0.0, y: 0.0, width: width as f32, height: height as f32, primary: true, scale: 1.0 }
+    })
+}
+
 pub fn get_screen_size() -> (usize, usize) {
     // SAFETY:
     // GetSystemMetrics is a safe FFI function that returns an integer (c_int).
@@ -100,3 +216,107 @@ pub fn get_screen_size() -> (usize, usize) {
     let (width, height) = unsafe { (GetSystemMetrics(SM_CXSCREEN), GetSystemMetrics(SM_CYSCREEN)) };
     (width as usize, height as usize)
 }
+
+/// What a global hotkey asks the overlay to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HotkeyAction {
+    TogglePause,
+    ClearBuffer,
+    FontLarger,
+    FontSmaller,
+    ToggleDebugWindow,
+    CommitInterim,
+}
+
+/// A key combination such as `ctrl+alt+h`, as understood by RegisterHotKey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Hotkey {
+    modifiers: HOT_KEY_MODIFIERS,
+    key: u32,
+}
+
+/// Parses `ctrl+alt+h`-style combinations: any of ctrl/alt/shift/win, then one letter, digit, F1-F24,
+/// `space`, `enter`, `plus` or `minus`.
+pub(crate) fn parse_hotkey(combo: &str) -> Result<Hotkey, String> {
+    let mut modifiers = HOT_KEY_MODIFIERS(0);
+    let mut key = None;
+    for part in combo.split('+').map(|p| p.trim().to_lowercase()) {
+        match part.as_str() {
+            "ctrl" | "control" => modifiers |= MOD_CONTROL,
+            "alt" => modifiers |= MOD_ALT,
+            "shift" => modifiers |= MOD_SHIFT,
+            "win" => modifiers |= MOD_WIN,
+            _ if key.is_some() => return Err(format!("hotkey `{}` has more than one key", combo)),
+            "space" => key = Some(0x20),
+            "enter" => key = Some(0x0D),
+            // VK_OEM_PLUS / VK_OEM_MINUS, the keys next to backspace.
+            "plus" => key = Some(0xBB),
+            "minus" => key = Some(0xBD),
+            p if p.len() == 1 && p.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                key = Some(p.to_ascii_uppercase().as_bytes()[0] as u32);
+            }
+            p if p.starts_with('f') && matches!(p[1..].parse::<u32>(), Ok(1..=24)) => {
+                // VK_F1 is 0x70 and the rest follow in order.
+                key = Some(0x6F + p[1..].parse::<u32>().expect("checked"));
+            }
+            p => return Err(format!("unknown key `{}` in hotkey `{}`", p, combo)),
+        }
+    }
+    let key = key.ok_or_else(|| format!("hotkey `{}` has no key", combo))?;
+    if modifiers.0 == 0 {
+        return Err(format!("hotkey `{}` needs at least one of ctrl/alt/shift/win", combo));
+    }
+    Ok(Hotkey { modifiers, key })
+}
+
+/// Registers the hotkeys on a dedicated thread and forwards their presses.
+/// A combination another application already owns is skipped with a warning.
+pub(crate) fn start_hotkey_listener(bindings: Vec<(Hotkey, HotkeyAction)>) -> UnboundedReceiver<HotkeyAction> {
+    let (tx, rx) = unbounded_channel();
+    if bindings.is_empty() {
+        return rx;
+    }
+    std::thread::spawn(move || {
+        // Hotkeys without a window are posted to the message queue of the thread that registered them.
+        for (id, (hotkey, action)) in bindings.iter().enumerate() {
+            if let Err(e) = unsafe { RegisterHotKey(None, id as i32, hotkey.modifiers | MOD_NOREPEAT, hotkey.key) } {
+                log::warn!("Failed to register hotkey for {:?}: {}", action, e);
+            }
+        }
+        let mut msg = MSG::default();
+        while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+            if msg.message != WM_HOTKEY {
+                continue;
+            }
+            if let Some((_, action)) = bindings.get(msg.wParam.0) {
+                if tx.send(*action).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Id of the first running process whose executable name is `exe_name` (case-insensitive).
+pub fn find_process_id(exe_name: &str) -> Option<u32> {
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0).ok()?;
+        let mut entry = PROCESSENTRY32W {
+            dwSize: size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut found = None;
+        let mut more = Process32FirstW(snapshot, &mut entry).is_ok();
+        while more {
+            let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+            if String::from_utf16_lossy(&entry.szExeFile[..len]).eq_ignore_ascii_case(exe_name) {
+                found = Some(entry.th32ProcessID);
+                break;
+            }
+            more = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+        found
+    }
+}